@@ -1,4 +1,10 @@
 //! Provid some basic abstraction of shapes for nablo.
+//!
+//! # breaking changes
+//! - [`shape::shape_elements::Style::rotate`] is in radians now, it used to be in degrees.
+//! - [`shape::shape_elements::Style::transform_origin`] is relative to [`shape::shape_elements::Style::position`] now, it used to be an absolute point.
+//!
+//! both change how old serialized styles are read, convert them before loading.
 
 pub mod math;
 pub mod shape;
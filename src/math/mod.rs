@@ -210,7 +210,7 @@ impl Vec2 {
 
 impl Shape for Vec2 {
 	fn into_svg(&self, style: &Style) -> String {
-		format!("<path d='M {} T {}' {} />" , style.position.svg(), (style.position + *self).svg(), style.svg_basic_settings())
+		style.svg_wrap(format!("<path d='M {} T {}' {} />" , style.position.svg(), (style.position + *self).svg(), style.svg_basic_settings()))
 	}
 
 	#[cfg(feature = "vertexs")]
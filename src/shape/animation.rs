@@ -32,6 +32,8 @@ pub enum AnimationLinker {
 	Power(f32),
 	#[default] Linear,
 	Mutation,
+	/// jumps to end value in given steps, refer to css `steps()`, useful for frame animations
+	Steps(usize),
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
@@ -88,6 +90,10 @@ impl Linker {
 			},
 			AnimationLinker::Mutation => {
 				return start_value;
+			},
+			AnimationLinker::Steps(steps) => {
+				let steps = steps.max(1) as f32;
+				(x * steps).floor() / steps * (self.end_value - start_value) + start_value
 			}
 		}
 	}
@@ -107,6 +113,19 @@ impl Animation {
 		}
 	}
 
+	/// create a frame animation going through `frame_count` frames, each frame lasts `frame_time`. see more in [`crate::shape::shape_elements::SpriteSheet`]
+	pub fn frames(start_time: Duration, frame_count: usize, frame_time: Duration) -> Self {
+		Self {
+			start_value: 0.0,
+			start_time,
+			linkers: vec!(Linker {
+				end_value: frame_count as f32,
+				sustain_time: frame_time * frame_count as u32,
+				linker: AnimationLinker::Steps(frame_count),
+			})
+		}
+	}
+
 	/// get how long the animation sustains
 	#[inline]
 	pub fn len(&self) -> Duration {
@@ -0,0 +1,444 @@
+//! Provid a glyph atlas, which keeps rasterized glyphs in one texture so text can be drawn as textured quads.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use fontdue::Font;
+use once_cell::sync::Lazy;
+use crate::math::Area;
+use crate::math::Vec2;
+
+/// the global glyph atlas, see more in [`GlyphAtlas::global`].
+static ATLAS: Lazy<Mutex<GlyphAtlas>> = Lazy::new(|| Mutex::new(GlyphAtlas::new(GlyphAtlas::DEFAULT_SIZE, AtlasMode::default())));
+
+/// what pixels in [`GlyphAtlas`] mean
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub enum AtlasMode {
+	/// coverage of glyphs, rasterized in every font size used. draw it as a alpha mask.
+	#[default] Alpha,
+	/// signed distance field rasterized once in `size` pixels, can be scaled to any font size and stays crisp.
+	/// 0.5 is the edge of glyph, 1.0 is `spread` pixels inside and 0.0 is `spread` pixels outside, in `size` pixels.
+	/// draw it with something like `smoothstep(0.5 - w, 0.5 + w, alpha)`.
+	Sdf {
+		size: f32,
+		spread: f32,
+	},
+}
+
+/// a glyph kept in [`GlyphAtlas`]
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct AtlasGlyph {
+	/// where the glyph is in atlas, (0.0, 0.0) for left top and (1.0, 1.0) for right bottom.
+	pub uv: Area,
+	/// where the bitmap is relative to pen position on baseline, in pixels of `raster_size`
+	pub offset: Vec2,
+	/// how large the bitmap is, in pixels of `raster_size`
+	pub size: Vec2,
+	/// font size the glyph is rasterized in, scale `offset` and `size` by `font_size / raster_size` to draw.
+	pub raster_size: f32,
+}
+
+impl AtlasGlyph {
+	/// where the bitmap should be drawn for a glyph of `font_size` with pen at `origin`
+	pub fn area(&self, origin: Vec2, font_size: f32) -> Area {
+		let scale = font_size / self.raster_size;
+		let left_top = origin + self.offset * scale;
+		Area::new(left_top, left_top + self.size * scale)
+	}
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct GlyphKey {
+	font_hash: usize,
+	glyph_index: u16,
+	/// quarter pixels of font size in [`AtlasMode::Alpha`], 0 for [`AtlasMode::Sdf`]
+	size: u32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Entry {
+	glyph: AtlasGlyph,
+	shelf: usize,
+}
+
+/// a row of glyphs with same height, glyphs are evicted shelf by shelf.
+#[derive(Clone, Debug, PartialEq, Default)]
+struct Shelf {
+	y: u32,
+	height: u32,
+	/// where the next glyph goes
+	cursor: u32,
+	last_used: u64,
+	glyphs: Vec<GlyphKey>,
+}
+
+/// rasterized glyphs packed into one square alpha texture, glyphs unused for a while are evicted when there is no space.
+///
+/// [`crate::shape::shape_elements::Text`] and [`crate::shape::shape_elements::RichText`] are converted into quads
+/// sampling [`GlyphAtlas::global`], with [`crate::shape::shape_elements::DrawCommand::texture`] set to [`GlyphAtlas::TEXTURE_ID`].
+/// a renderer should upload [`Self::pixels`] or [`Self::rgba`] when [`Self::take_dirty`] gives something, and call [`Self::begin_frame`] every frame.
+#[derive(Clone, Debug, PartialEq)]
+pub struct GlyphAtlas {
+	size: u32,
+	mode: AtlasMode,
+	/// one byte per pixel, row by row from top left
+	pixels: Vec<u8>,
+	glyphs: HashMap<GlyphKey, Entry>,
+	shelves: Vec<Shelf>,
+	frame: u64,
+	revision: u64,
+	/// pixels changed since last [`Self::take_dirty`]
+	dirty: Option<Area>,
+}
+
+impl GlyphAtlas {
+	/// width and height of [`Self::global`] at beginning
+	pub const DEFAULT_SIZE: u32 = 1024;
+	/// the texture id of glyph atlas in [`crate::shape::shape_elements::DrawCommand::texture`]
+	pub const TEXTURE_ID: &'static str = "nablo_glyph_atlas";
+	/// empty pixels around every glyph, so sampling will not bleed into neighbours
+	const GUTTER: u32 = 1;
+	/// rows at top kept for the white block, see more in [`Self::white_uv`]
+	const RESERVED: u32 = 4;
+
+	/// create a empty atlas in `size` * `size` pixels
+	pub fn new(size: u32, mode: AtlasMode) -> Self {
+		let mut back = Self {
+			size,
+			mode,
+			pixels: vec!(0; (size * size) as usize),
+			glyphs: HashMap::new(),
+			shelves: vec!(),
+			frame: 0,
+			revision: 0,
+			dirty: None,
+		};
+		back.fill_white();
+		back
+	}
+
+	/// get the atlas used by text vertexs
+	///
+	/// Note: do not call this again or convert any text into vertexs before the returned guard is dropped.
+	pub fn global() -> MutexGuard<'static, GlyphAtlas> {
+		ATLAS.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// width and height of the atlas in pixels
+	pub fn size(&self) -> u32 {
+		self.size
+	}
+
+	/// change size of the atlas, every glyph is thrown away.
+	pub fn set_size(&mut self, size: u32) {
+		*self = Self::new(size, self.mode);
+		self.mark_all_dirty();
+	}
+
+	pub fn mode(&self) -> AtlasMode {
+		self.mode
+	}
+
+	/// change what pixels mean, every glyph is thrown away.
+	pub fn set_mode(&mut self, mode: AtlasMode) {
+		*self = Self::new(self.size, mode);
+		self.mark_all_dirty();
+	}
+
+	/// throw away every glyph
+	pub fn clear(&mut self) {
+		self.pixels.fill(0);
+		self.fill_white();
+		self.glyphs.clear();
+		self.shelves.clear();
+		self.mark_all_dirty();
+	}
+
+	/// how many glyphs are kept
+	pub fn len(&self) -> usize {
+		self.glyphs.len()
+	}
+
+	/// check if there is no glyph kept
+	pub fn is_empty(&self) -> bool {
+		self.glyphs.is_empty()
+	}
+
+	/// alpha of every pixel, row by row from top left
+	pub fn pixels(&self) -> &[u8] {
+		&self.pixels
+	}
+
+	/// same as [`Self::pixels`], but in white rgba8 so it can be drawn like a image tinted by vertex color.
+	pub fn rgba(&self) -> Vec<u8> {
+		self.pixels.iter().flat_map(|alpha| [255, 255, 255, *alpha]).collect()
+	}
+
+	/// where pixels are fully covered, solid shapes such as text decorations sample here so they can be drawn with glyphs in one command.
+	/// it is never evicted.
+	pub fn white_uv(&self) -> Vec2 {
+		Vec2::same(2.0 / self.size as f32)
+	}
+
+	/// a number which changes every time pixels change
+	pub fn revision(&self) -> u64 {
+		self.revision
+	}
+
+	/// get pixels changed since last call in pixels, [`Option::None`] if nothing changed.
+	pub fn take_dirty(&mut self) -> Option<Area> {
+		self.dirty.take()
+	}
+
+	/// tell the atlas a new frame begins, glyphs not used in current frame can be evicted.
+	///
+	/// without calling this, glyphs are never evicted and glyphs without space will not be drawn.
+	pub fn begin_frame(&mut self) {
+		self.frame += 1;
+	}
+
+	/// get a glyph from the atlas, rasterize it if not found. [`Option::None`] if the glyph is empty or there is no space.
+	pub fn glyph(&mut self, font: &Font, glyph_index: u16, font_size: f32) -> Option<AtlasGlyph> {
+		let key = GlyphKey {
+			font_hash: font.file_hash(),
+			glyph_index,
+			size: match self.mode {
+				AtlasMode::Alpha => (font_size * 4.0).round() as u32,
+				AtlasMode::Sdf { .. } => 0,
+			},
+		};
+		if let Some(entry) = self.glyphs.get(&key) {
+			self.shelves[entry.shelf].last_used = self.frame;
+			return Some(entry.glyph)
+		}
+		let (raster_size, padding, bitmap) = match self.mode {
+			AtlasMode::Alpha => {
+				let raster_size = key.size as f32 / 4.0;
+				let (metrics, coverage) = font.rasterize_indexed(glyph_index, raster_size);
+				(raster_size, 0, Bitmap::new(metrics, coverage))
+			},
+			AtlasMode::Sdf { size, spread } => {
+				let (metrics, coverage) = font.rasterize_indexed(glyph_index, size);
+				let padding = spread.ceil().max(1.0) as u32;
+				(size, padding, Bitmap::new(metrics, coverage).into_sdf(padding, spread))
+			},
+		};
+		if bitmap.width == 0 || bitmap.height == 0 {
+			return None
+		}
+		let (x, y, shelf) = self.allocate(bitmap.width + Self::GUTTER * 2, bitmap.height + Self::GUTTER * 2)?;
+		let (x, y) = (x + Self::GUTTER, y + Self::GUTTER);
+		for row in 0..bitmap.height {
+			let start = ((y + row) * self.size + x) as usize;
+			let source = (row * bitmap.width) as usize;
+			self.pixels[start..start + bitmap.width as usize].copy_from_slice(&bitmap.pixels[source..source + bitmap.width as usize]);
+		}
+		let area = Area::new(Vec2::new(x as f32, y as f32), Vec2::new((x + bitmap.width) as f32, (y + bitmap.height) as f32));
+		self.mark_dirty(area);
+		let glyph = AtlasGlyph {
+			uv: Area::new(area.area[0] / self.size as f32, area.area[1] / self.size as f32),
+			offset: Vec2::new(bitmap.xmin - padding as f32, - bitmap.ymin - bitmap.height as f32 + padding as f32),
+			size: Vec2::new(bitmap.width as f32, bitmap.height as f32),
+			raster_size,
+		};
+		self.glyphs.insert(key, Entry { glyph, shelf });
+		self.shelves[shelf].glyphs.push(key);
+		Some(glyph)
+	}
+
+	/// find space for a `width` * `height` rect, evicting a shelf if needed, returns left top and the shelf.
+	fn allocate(&mut self, width: u32, height: u32) -> Option<(u32, u32, usize)> {
+		if width > self.size || height > self.size {
+			return None
+		}
+		// the lowest shelf which fits, too tall shelves waste too much
+		let fitting = self.shelves.iter().enumerate().filter(|(_, t)| t.height >= height && t.height <= height * 2 && t.cursor + width <= self.size).min_by_key(|(_, t)| t.height).map(|(i, _)| i);
+		let index = match fitting {
+			Some(t) => t,
+			None => {
+				let bottom = self.shelves.iter().map(|t| t.y + t.height).max().unwrap_or(Self::RESERVED);
+				if bottom + height <= self.size {
+					self.shelves.push(Shelf {
+						y: bottom,
+						height,
+						..Default::default()
+					});
+					self.shelves.len() - 1
+				}else {
+					let index = self.shelves.iter().enumerate().filter(|(_, t)| t.height >= height && t.last_used < self.frame).min_by_key(|(_, t)| t.last_used).map(|(i, _)| i)?;
+					self.evict(index);
+					index
+				}
+			},
+		};
+		let shelf = &mut self.shelves[index];
+		let x = shelf.cursor;
+		shelf.cursor += width;
+		shelf.last_used = self.frame;
+		Some((x, shelf.y, index))
+	}
+
+	/// throw away every glyph in a shelf
+	fn evict(&mut self, index: usize) {
+		let shelf = &mut self.shelves[index];
+		for key in shelf.glyphs.drain(..) {
+			self.glyphs.remove(&key);
+		}
+		shelf.cursor = 0;
+		let (y, height) = (shelf.y, shelf.height);
+		let start = (y * self.size) as usize;
+		self.pixels[start..start + (height * self.size) as usize].fill(0);
+		self.mark_dirty(Area::new(Vec2::new(0.0, y as f32), Vec2::new(self.size as f32, (y + height) as f32)));
+	}
+
+	/// a 2 * 2 white block with gutter around at left top
+	fn fill_white(&mut self) {
+		if self.size < Self::RESERVED {
+			return;
+		}
+		for y in 1..3 {
+			for x in 1..3 {
+				self.pixels[(y * self.size + x) as usize] = 255;
+			}
+		}
+	}
+
+	fn mark_dirty(&mut self, area: Area) {
+		self.revision += 1;
+		match &mut self.dirty {
+			Some(t) => t.combine(&area),
+			None => self.dirty = Some(area),
+		}
+	}
+
+	fn mark_all_dirty(&mut self) {
+		self.mark_dirty(Area::new(Vec2::ZERO, Vec2::same(self.size as f32)));
+	}
+}
+
+/// a rasterized glyph
+struct Bitmap {
+	width: u32,
+	height: u32,
+	xmin: f32,
+	ymin: f32,
+	pixels: Vec<u8>,
+}
+
+impl Bitmap {
+	fn new(metrics: fontdue::Metrics, pixels: Vec<u8>) -> Self {
+		Self {
+			width: metrics.width as u32,
+			height: metrics.height as u32,
+			xmin: metrics.xmin as f32,
+			ymin: metrics.ymin as f32,
+			pixels,
+		}
+	}
+
+	/// turn coverage into signed distance field, `padding` pixels are added around.
+	fn into_sdf(self, padding: u32, spread: f32) -> Self {
+		if self.width == 0 || self.height == 0 {
+			return self
+		}
+		let width = self.width + padding * 2;
+		let height = self.height + padding * 2;
+		let is_inside = |x: i64, y: i64| {
+			let (x, y) = (x - padding as i64, y - padding as i64);
+			x >= 0 && y >= 0 && x < self.width as i64 && y < self.height as i64 && self.pixels[(y * self.width as i64 + x) as usize] >= 128
+		};
+		let radius = padding as i64;
+		let mut pixels = Vec::with_capacity((width * height) as usize);
+		for y in 0..height as i64 {
+			for x in 0..width as i64 {
+				let inside = is_inside(x, y);
+				// nearest pixel on the other side, searched in a square since spread is small
+				let mut nearest = f32::MAX;
+				for dy in -radius..=radius {
+					for dx in -radius..=radius {
+						if is_inside(x + dx, y + dy) != inside {
+							nearest = nearest.min(((dx * dx + dy * dy) as f32).sqrt());
+						}
+					}
+				}
+				let distance = (nearest - 0.5).min(spread);
+				let distance = if inside { distance } else { - distance };
+				pixels.push(((0.5 + distance / spread / 2.0).clamp(0.0, 1.0) * 255.0).round() as u8);
+			}
+		}
+		Self {
+			width,
+			height,
+			xmin: self.xmin,
+			ymin: self.ymin,
+			pixels,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn white_block_is_reserved() {
+		let atlas = GlyphAtlas::new(64, AtlasMode::Alpha);
+		let uv = atlas.white_uv() * 64.0;
+		assert_eq!(atlas.pixels()[(uv.y as u32 * 64 + uv.x as u32) as usize], 255);
+		assert!(atlas.is_empty());
+		assert_eq!(atlas.rgba().len(), 64 * 64 * 4);
+	}
+
+	#[cfg(feature = "default-font")]
+	fn font() -> Font {
+		Font::from_bytes(crate::shape::shape_elements::DEFAULT_FONT as &[u8], Default::default()).unwrap()
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn glyphs_are_packed_once() {
+		let font = font();
+		let mut atlas = GlyphAtlas::new(256, AtlasMode::Alpha);
+		let a = atlas.glyph(&font, font.lookup_glyph_index('a'), 16.0).unwrap();
+		let b = atlas.glyph(&font, font.lookup_glyph_index('b'), 16.0).unwrap();
+		assert!(atlas.take_dirty().is_some());
+		let revision = atlas.revision();
+		assert_eq!(atlas.glyph(&font, font.lookup_glyph_index('a'), 16.0), Some(a));
+		assert_eq!((atlas.len(), atlas.revision()), (2, revision));
+		assert!(atlas.take_dirty().is_none());
+		// no overlap and never on the white block
+		assert!(a.uv.area[1].x <= b.uv.area[0].x || b.uv.area[1].x <= a.uv.area[0].x || a.uv.area[1].y <= b.uv.area[0].y || b.uv.area[1].y <= a.uv.area[0].y);
+		assert!(a.uv.area[0].y * 256.0 >= GlyphAtlas::RESERVED as f32);
+		assert_eq!(a.raster_size, 16.0);
+		assert_eq!(a.area(Vec2::ZERO, 32.0).width(), a.size.x * 2.0);
+		// spaces have no pixels
+		assert!(atlas.glyph(&font, font.lookup_glyph_index(' '), 16.0).is_none());
+		atlas.clear();
+		assert!(atlas.is_empty());
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn unused_glyphs_are_evicted() {
+		let font = font();
+		let w = font.lookup_glyph_index('W');
+		let mut atlas = GlyphAtlas::new(64, AtlasMode::Alpha);
+		assert!(atlas.glyph(&font, w, 48.0).is_some());
+		// no space, and the only glyph is used in current frame
+		assert!(atlas.glyph(&font, w, 47.0).is_none());
+		atlas.begin_frame();
+		assert!(atlas.glyph(&font, w, 47.0).is_some());
+		assert_eq!(atlas.len(), 1);
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn sdf_glyphs_ignore_font_size() {
+		let font = font();
+		let mut atlas = GlyphAtlas::new(256, AtlasMode::Sdf { size: 32.0, spread: 4.0 });
+		let small = atlas.glyph(&font, font.lookup_glyph_index('o'), 12.0).unwrap();
+		let large = atlas.glyph(&font, font.lookup_glyph_index('o'), 64.0).unwrap();
+		assert_eq!(small, large);
+		assert_eq!((atlas.len(), small.raster_size), (1, 32.0));
+	}
+}
@@ -1,11 +1,19 @@
 //! Provid some basic abstraction of shapes.
 pub mod shape_elements;
 pub mod animation;
+pub mod registry;
+pub mod atlas;
 
 use std::ops::IndexMut;
 use std::ops::Index;
+use std::f32::consts::PI;
+use std::sync::Arc;
 use crate::math::Area;
 use crate::math::Vec2;
+use crate::shape::animation::StyleToAnimate;
+use crate::shape::registry::ImageRegistry;
+#[cfg(feature = "vertexs")]
+use crate::shape::atlas::GlyphAtlas;
 use crate::shape::shape_elements::Shape as ShapeTrait;
 use crate::shape::shape_elements::*;
 use crate::shape::shape_elements::Style;
@@ -51,7 +59,9 @@ pub struct Painter {
 	pub offset: Vec2,
 	shapes: Vec<Shape>,
 	style: Style,
-	text_style: TextStyle
+	text_style: TextStyle,
+	#[serde(skip)]
+	image_registry: Option<Arc<ImageRegistry>>,
 }
 
 impl Index<usize> for Painter {
@@ -74,7 +84,8 @@ impl Default for Painter {
 			style: Style::default(),
 			paint_area: Area::default(),
 			offset: Vec2::same(0.0),
-			text_style: TextStyle::default()
+			text_style: TextStyle::default(),
+			image_registry: None,
 		}
 	}
 }
@@ -104,9 +115,15 @@ impl Painter {
 		self.style.size = size;
 	}
 
-	/// set color of a shape, Note: this function only affects on newly added shapes.
+	/// set color of a shape, will also remove paint set by [`Self::set_fill_paint`], Note: this function only affects on newly added shapes.
 	pub fn set_color(&mut self, fill: impl Into<Color>) {
 		self.style.fill = fill.into();
+		self.style.fill_paint = None;
+	}
+
+	/// fill a shape with gradient or other paints, Note: this function only affects on newly added shapes.
+	pub fn set_fill_paint(&mut self, paint: impl Into<Paint>) {
+		self.style.fill_paint = Some(paint.into());
 	}
 
 	/// set stroke width of a shape, Note: this function only affects on newly added shapes.
@@ -114,9 +131,50 @@ impl Painter {
 		self.style.stroke_width = width;
 	}
 
-	/// set stroke color of a shape, Note: this function only affects on newly added shapes.
+	/// set stroke color of a shape, will also remove paint set by [`Self::set_stroke_paint`], Note: this function only affects on newly added shapes.
 	pub fn set_stroke_color(&mut self, color: impl Into<Color>) {
 		self.style.stroke_color = color.into();
+		self.style.stroke_paint = None;
+	}
+
+	/// stroke a shape with gradient or other paints, Note: this function only affects on newly added shapes.
+	pub fn set_stroke_paint(&mut self, paint: impl Into<Paint>) {
+		self.style.stroke_paint = Some(paint.into());
+	}
+
+	/// set how the end of an open stroke looks like, Note: this function only affects on newly added shapes.
+	pub fn set_line_cap(&mut self, line_cap: LineCap) {
+		self.style.line_cap = line_cap;
+	}
+
+	/// set how stroke segments are connected, Note: this function only affects on newly added shapes.
+	pub fn set_line_join(&mut self, line_join: LineJoin) {
+		self.style.line_join = line_join;
+	}
+
+	/// set miter limit of stroke, Note: this function only affects on newly added shapes.
+	pub fn set_miter_limit(&mut self, miter_limit: f32) {
+		self.style.miter_limit = miter_limit;
+	}
+
+	/// set how shapes mix with what is already drawn, Note: this function only affects on newly added shapes.
+	pub fn set_blend_mode(&mut self, blend_mode: BlendMode) {
+		self.style.blend_mode = blend_mode;
+	}
+
+	/// set opacity of a whole shape, Note: this function only affects on newly added shapes.
+	pub fn set_opacity(&mut self, opacity: f32) {
+		self.style.opacity = opacity;
+	}
+
+	/// add a effect like shadow or blur, Note: this function only affects on newly added shapes.
+	pub fn add_effect(&mut self, effect: Effect) {
+		self.style.effects.push(effect);
+	}
+
+	/// remove all effects, Note: this function only affects on newly added shapes.
+	pub fn clear_effects(&mut self) {
+		self.style.effects.clear();
 	}
 
 	/// set which layer will we paint, Note: this function only affects on newly added shapes.
@@ -130,11 +188,34 @@ impl Painter {
 		self.style.position = self.style.position + self.offset;
 	}
 
-	/// set clip color of a shape, Note: this function only affects on newly added shapes.
+	/// set clip of a shape, replaces current clip as it always did, use [`Self::intersect_clip`] for nested clips. Note: this function only affects on newly added shapes.
 	pub fn set_clip(&mut self, clip: Area) {
 		self.style.clip = clip
 	}
 
+	/// clip shapes with the cross part of current clip and given clip, so nested clips never show more than outer ones.
+	/// a unset clip ([`Area::ZERO`], such as in [`Painter::default`]) is taken as unbounded. Note: this function only affects on newly added shapes.
+	pub fn intersect_clip(&mut self, clip: Area) {
+		self.style.clip = if self.style.clip == Area::ZERO {
+			clip
+		}else {
+			self.style.clip.cross_part(&clip)
+		}
+	}
+
+	/// clip shapes with a mask at current position, intersects with masks already pushed. Note: this function only affects on newly added shapes.
+	pub fn push_clip_mask(&mut self, mask: ShapeMask) {
+		self.style.clip_masks.push(ClipMask {
+			mask,
+			position: self.style.position,
+		})
+	}
+
+	/// remove the last pushed clip mask, Note: this function only affects on newly added shapes.
+	pub fn pop_clip_mask(&mut self) -> Option<ClipMask> {
+		self.style.clip_masks.pop()
+	}
+
 	/// set text to paint bold or not
 	pub fn set_text_bold(&mut self, is_bold: bool) {
 		self.text_style = self.text_style.clone().set_bold(is_bold);
@@ -145,6 +226,66 @@ impl Painter {
 		self.text_style = self.text_style.clone().set_italic(is_italic);
 	}
 
+	/// set font family of text, [`Option::None`] for default family. see more in [`crate::shape::registry::FontRegistry`]
+	pub fn set_text_font_family(&mut self, font_family: Option<String>) {
+		self.text_style = self.text_style.clone().set_font_family(font_family);
+	}
+
+	/// set font size of text in pixels, Note: this function only affects on newly added shapes.
+	pub fn set_font_size(&mut self, font_size: f32) {
+		self.text_style = self.text_style.clone().set_font_size(font_size);
+	}
+
+	/// set line height of text, times of font size, Note: this function only affects on newly added shapes.
+	pub fn set_line_height(&mut self, line_height: f32) {
+		self.text_style = self.text_style.clone().set_line_height(line_height);
+	}
+
+	/// set extra space after every character of text, Note: this function only affects on newly added shapes.
+	pub fn set_letter_spacing(&mut self, letter_spacing: f32) {
+		self.text_style = self.text_style.clone().set_letter_spacing(letter_spacing);
+	}
+
+	/// set extra space after every space of text, Note: this function only affects on newly added shapes.
+	pub fn set_word_spacing(&mut self, word_spacing: f32) {
+		self.text_style = self.text_style.clone().set_word_spacing(word_spacing);
+	}
+
+	/// set how lines of text are placed horizontally, Note: this function only affects on newly added shapes.
+	pub fn set_text_align(&mut self, align: TextAlign) {
+		self.text_style = self.text_style.clone().set_align(align);
+	}
+
+	/// set how lines of text are placed vertically, Note: this function only affects on newly added shapes.
+	pub fn set_text_vertical_align(&mut self, vertical_align: TextVerticalAlign) {
+		self.text_style = self.text_style.clone().set_vertical_align(vertical_align);
+	}
+
+	/// set how text breaks lines, Note: this function only affects on newly added shapes.
+	pub fn set_text_wrap(&mut self, wrap: TextWrap) {
+		self.text_style = self.text_style.clone().set_wrap(wrap);
+	}
+
+	/// set what to do with text outside its box, Note: this function only affects on newly added shapes.
+	pub fn set_text_overflow(&mut self, overflow: TextOverflow) {
+		self.text_style = self.text_style.clone().set_overflow(overflow);
+	}
+
+	/// set underline, overline and strikethrough of text, Note: this function only affects on newly added shapes.
+	pub fn set_text_decoration(&mut self, decoration: TextDecoration) {
+		self.text_style = self.text_style.clone().set_decoration(decoration);
+	}
+
+	/// set thickness of text decoration lines in pixels, Note: this function only affects on newly added shapes.
+	pub fn set_text_decoration_thickness(&mut self, thickness: f32) {
+		self.text_style = self.text_style.clone().set_decoration_thickness(thickness);
+	}
+
+	/// set color of text decoration lines, Note: this function only affects on newly added shapes.
+	pub fn set_text_decoration_color(&mut self, color: impl Into<Color>) {
+		self.text_style = self.text_style.clone().set_decoration_color(color);
+	}
+
 	/// set text style dirctly
 	pub fn set_text_style(&mut self, text_style: TextStyle) {
 		self.text_style = text_style;
@@ -160,7 +301,12 @@ impl Painter {
 			..Default::default()
 		};
 
-		if (shape.style.fill[3] == 0) && (shape.style.stroke_color[3] == 0 || shape.style.stroke_width == 0.0) {
+		let is_visible = match &shape.shape {
+			ShapeElement::Group(_) => true,
+			ShapeElement::RichText(t) if t.spans.iter().any(|span| span.color.is_some_and(|color| color[3] != 0)) => true,
+			_ => shape.style.get_fill_paint().is_visible() || (shape.style.get_stroke_paint().is_visible() && shape.style.stroke_width != 0.0)
+		};
+		if shape.style.opacity <= 0.0 || !is_visible {
 			return None
 		}
 
@@ -209,23 +355,80 @@ impl Painter {
 		))
 	}
 
-	/// draw a image. see more in [`Self::draw`]
+	/// draw a group of shapes, positions of shapes are relative to the group. see more in [`Self::draw`]
+	///
+	/// you can get shapes from another [`Painter`] by `painter.into()`.
+	pub fn group(&mut self, shapes: Vec<Shape>) -> Option<usize> {
+		self.draw(ShapeElement::Group(shapes))
+	}
+
+	/// draw a image, `intrinsic_size` will be filled if the image is in [`Self::image_registry`]. see more in [`Self::draw`]
 	pub fn image(&mut self, id: impl Into<String>, size: Vec2) -> Option<usize> {
 		let id = id.into();
 		self.draw(ShapeElement::Image(Image {
+			intrinsic_size: self.intrinsic_size_of(&id),
 			id,
 			size,
-			mask: None
+			mask: None,
+			..Default::default()
 		}))
 	}
 
+	/// draw a image in its intrinsic size from [`Self::image_registry`], draws nothing and returns [`Option::None`] if the image is not registered. see more in [`Self::draw`]
+	pub fn image_auto_size(&mut self, id: impl Into<String>) -> Option<usize> {
+		let id = id.into();
+		let size = self.intrinsic_size_of(&id)?;
+		self.image(id, size)
+	}
+
 	/// draw a image with mask. see more in [`Self::draw`]
 	pub fn image_mask(&mut self, id: impl Into<String>, size: Vec2, mask: ShapeMask) -> Option<usize> {
 		let id = id.into();
 		self.draw(ShapeElement::Image(Image {
+			intrinsic_size: self.intrinsic_size_of(&id),
 			id,
 			size,
-			mask: Some(mask)
+			mask: Some(mask),
+			..Default::default()
+		}))
+	}
+
+	/// draw a frame of a sprite sheet, animate [`StyleToAnimate::Id`] with [`Image::FRAME_ID`] to change frame.
+	/// if you animate the returned shape through [`Animate`] yourself, the id is [`Style::FIXED_LEN`] + [`Image::FRAME_ID`]. see more in [`Self::draw`]
+	pub fn sprite(&mut self, id: impl Into<String>, size: Vec2, sprite: SpriteSheet) -> Option<usize> {
+		let id = id.into();
+		self.draw(ShapeElement::Image(Image {
+			intrinsic_size: self.intrinsic_size_of(&id),
+			id,
+			size,
+			sprite: Some(sprite),
+			..Default::default()
+		}))
+	}
+
+	/// set where to find intrinsic sizes of images
+	pub fn set_image_registry(&mut self, registry: Arc<ImageRegistry>) {
+		self.image_registry = Some(registry);
+	}
+
+	/// get current image registry
+	pub fn image_registry(&self) -> Option<&ImageRegistry> {
+		self.image_registry.as_deref()
+	}
+
+	fn intrinsic_size_of(&self, id: &str) -> Option<Vec2> {
+		self.image_registry.as_ref().and_then(|t| t.size_of(id))
+	}
+
+	/// draw a nine-slice image, corners keep unscaled when size changes. see more in [`Self::draw`]
+	pub fn image_nine_slice(&mut self, id: impl Into<String>, size: Vec2, intrinsic_size: Vec2, nine_slice: NineSlice) -> Option<usize> {
+		let id = id.into();
+		self.draw(ShapeElement::Image(Image {
+			id,
+			size,
+			intrinsic_size: Some(intrinsic_size),
+			nine_slice: Some(nine_slice),
+			..Default::default()
 		}))
 	}
 
@@ -240,6 +443,35 @@ impl Painter {
 		}))
 	}
 
+	/// draw a text made of spans, alignment, wrapping and overflow follow text style of this painter. see more in [`Self::draw`]
+	pub fn rich_text(&mut self, spans: Vec<TextSpan>) -> Option<usize> {
+		self.draw(ShapeElement::RichText(RichText {
+			spans,
+			text_style: self.text_style.clone(),
+			..Default::default()
+		}))
+	}
+
+	/// draw a text following `path`, see more in [`TextPath`] and [`Self::draw`]
+	pub fn text_on_path(&mut self, text: String, path: TextPathGuide) -> Option<usize> {
+		self.draw(ShapeElement::TextPath(TextPath {
+			text,
+			path,
+			text_style: self.text_style.clone(),
+			..Default::default()
+		}))
+	}
+
+	/// draw a text made of spans with width limit. see more in [`Self::draw`]
+	pub fn rich_text_with_width(&mut self, spans: Vec<TextSpan>, width: f32) -> Option<usize> {
+		self.draw(ShapeElement::RichText(RichText {
+			spans,
+			text_width: Some(width),
+			text_style: self.text_style.clone(),
+			..Default::default()
+		}))
+	}
+
 	/// get how large do a text take without break.
 	pub fn text_area(&self, text: String) -> Area {
 		let text = Shape {
@@ -283,7 +515,7 @@ impl Painter {
 		}))
 	}
 
-	/// draw a text with limit. see more in [`Self::draw`]
+	/// draw a text with limit, text outside is handled by overflow of current text style, see [`Self::set_text_overflow`]. see more in [`Self::draw`]
 	pub fn text_with_limit(&mut self, text: String, width: f32, height: f32) -> Option<usize> {
 		self.draw(ShapeElement::Text(Text {
 			text,
@@ -325,6 +557,9 @@ impl Painter {
 	pub fn move_delta_to(&mut self, delta: Vec2){
 		self.paint_area.move_delta_to(delta);
 		self.style.clip.move_delta_to(delta);
+		for mask in &mut self.style.clip_masks {
+			mask.move_delta_to(delta);
+		}
 		self.style.position = self.style.position + delta;
 		self.style.transform_origin = self.style.transform_origin + delta;
 		for shape in &mut self.shapes {
@@ -336,7 +571,9 @@ impl Painter {
 	pub fn brighter(&mut self, factor: f32){
 		for shape in &mut self.shapes {
 			shape.style.fill = shape.style.fill.brighter(factor);
-			shape.style.stroke_color = shape.style.stroke_color.brighter(factor)
+			shape.style.stroke_color = shape.style.stroke_color.brighter(factor);
+			shape.style.fill_paint = shape.style.fill_paint.take().map(|t| t.brighter(factor));
+			shape.style.stroke_paint = shape.style.stroke_paint.take().map(|t| t.brighter(factor));
 		}
 	}
 
@@ -384,6 +621,29 @@ impl Painter {
 		self.shapes.push(shape)
 	}
 
+	/// convert every drawn shapes into vertexs, shapes next to each other with same render state will be merged into one [`DrawCommand`].
+	#[cfg(feature = "vertexs")]
+	pub fn draw_list(&self, size: Vec2) -> Vec<DrawCommand> {
+		let mut back: Vec<DrawCommand> = vec!();
+		for shape in &self.shapes {
+			for command in shape.into_draw_commands(size) {
+				if let Some(last) = back.last_mut() {
+					if last.is_same_state(&command) {
+						last.append(command);
+						continue;
+					}
+				}
+				back.push(command);
+			}
+		}
+		back
+	}
+
+	/// find the top most shape contains given point, see more in [`Shape::is_point_inside`]
+	pub fn hit_test(&self, point: Vec2) -> Option<usize> {
+		self.shapes.iter().rposition(|shape| shape.is_point_inside(point))
+	}
+
 	/// get how large place did current shapes take
 	pub fn get_area(&self) -> Area {
 		let mut area = Area::ZERO;
@@ -416,17 +676,23 @@ pub enum ShapeElement {
 	Circle(Circle),
 	Rect(Rect),
 	Text(Text),
+	/// a text made of spans in different styles
+	RichText(RichText),
+	/// a text following a outline
+	TextPath(TextPath),
 	CubicBezier(CubicBezier),
 	Line(Vec2),
 	Polygon(Polygon),
-	Image(Image)
+	Image(Image),
+	/// a group of shapes, style of the group applies to every shape inside, positions of shapes inside are relative to the group.
+	Group(Vec<Shape>)
 }
 
 impl ShapeElement {
 	/// turn current [`ShapeElement`] into [`ShapeMask`]
 	///
 	/// # Panics
-	/// when meets `ShapeElement::Image(_)` or `ShapeElement::Text(_)` 
+	/// when meets `ShapeElement::Image(_)` or `ShapeElement::Group(_)` 
 	pub fn into_mask(&self) -> ShapeMask {
 		match self {
 			Self::Circle(t) => ShapeMask::Circle(t.clone()),
@@ -434,6 +700,9 @@ impl ShapeElement {
 			Self::CubicBezier(t) => ShapeMask::CubicBezier(t.clone()),
 			Self::Polygon(t) => ShapeMask::Polygon(t.clone()),
 			Self::Line(t) => ShapeMask::Line(t.clone()),
+			Self::Text(t) => ShapeMask::Text(t.clone()),
+			Self::RichText(t) => ShapeMask::RichText(t.clone()),
+			Self::TextPath(t) => ShapeMask::TextPath(t.clone()),
 			_ => unreachable!()
 		}
 	}
@@ -447,6 +716,75 @@ pub enum ShapeMask {
 	Line(Vec2),
 	CubicBezier(CubicBezier),
 	Polygon(Polygon),
+	/// glyph outlines of a text, laid out in [`TextStyle::resolved_font_size`] no matter how the mask is placed. see more in [`Text::to_outlines`].
+	Text(Text),
+	/// glyph outlines of a rich text, see more in [`RichText::to_outlines`].
+	RichText(RichText),
+	/// glyph outlines of a text following a path, see more in [`TextPath::to_outlines`].
+	TextPath(TextPath),
+}
+
+/// for [`ShapeElement::Group`], ids of every shape inside are placed one by one, see more in [`Shape`]'s [`Animate`] implementation.
+impl Animate for ShapeElement {
+	fn animate(&mut self, id: usize, change_to: f32) {
+		match self {
+			Self::Circle(t) => t.animate(id, change_to),
+			Self::Rect(t) => t.animate(id, change_to),
+			Self::Text(t) => t.animate(id, change_to),
+			Self::RichText(t) => t.animate(id, change_to),
+			Self::TextPath(t) => t.animate(id, change_to),
+			Self::CubicBezier(t) => t.animate(id, change_to),
+			Self::Line(t) => t.animate(id, change_to),
+			Self::Polygon(t) => t.animate(id, change_to),
+			Self::Image(t) => t.animate(id, change_to),
+			Self::Group(shapes) => {
+				let mut id = id;
+				for shape in shapes {
+					let len = shape.animate_len();
+					if id < len {
+						shape.animate(id, change_to);
+						return;
+					}
+					id -= len;
+				}
+			},
+		}
+	}
+
+	fn animate_len(&self) -> usize {
+		match self {
+			Self::Circle(t) => t.animate_len(),
+			Self::Rect(t) => t.animate_len(),
+			Self::Text(t) => t.animate_len(),
+			Self::RichText(t) => t.animate_len(),
+			Self::TextPath(t) => t.animate_len(),
+			Self::CubicBezier(t) => t.animate_len(),
+			Self::Line(t) => t.animate_len(),
+			Self::Polygon(t) => t.animate_len(),
+			Self::Image(t) => t.animate_len(),
+			Self::Group(shapes) => shapes.iter().map(|shape| shape.animate_len()).sum(),
+		}
+	}
+}
+
+/// fixed ids of style come first, then ids of shape element, ids of paints are last since they change when paints are replaced.
+///
+/// so id of a shape element is always [`Style::FIXED_LEN`] + its id in the element.
+impl Animate for Shape {
+	fn animate(&mut self, id: usize, change_to: f32) {
+		let element_len = self.shape.animate_len();
+		if id < Style::FIXED_LEN {
+			self.style.animate(id, change_to);
+		}else if id < Style::FIXED_LEN + element_len {
+			self.shape.animate(id - Style::FIXED_LEN, change_to);
+		}else {
+			self.style.animate(id - element_len, change_to);
+		}
+	}
+
+	fn animate_len(&self) -> usize {
+		self.style.len() + self.shape.animate_len()
+	}
 }
 
 impl Default for ShapeElement {
@@ -456,6 +794,47 @@ impl Default for ShapeElement {
 }
 
 impl ShapeMask {
+	/// pre-scale current mask with (0,0), see more in [`Shape::pre_scale`]
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		match self {
+			ShapeMask::Circle(t) => t.radius *= scale_factor,
+			ShapeMask::Rect(t) => {
+				t.width_and_height = t.width_and_height * scale_factor;
+				t.rounding = t.rounding * scale_factor;
+			},
+			ShapeMask::Line(t) => *t = *t * scale_factor,
+			ShapeMask::CubicBezier(t) => {
+				for point in &mut t.points {
+					*point = *point * scale_factor;
+				}
+			},
+			ShapeMask::Polygon(t) => {
+				for point in &mut t.points {
+					*point = *point * scale_factor;
+				}
+			},
+			ShapeMask::Text(t) => {
+				t.pre_scale(scale_factor);
+			},
+			ShapeMask::RichText(t) => {
+				t.pre_scale(scale_factor);
+			},
+			ShapeMask::TextPath(t) => {
+				t.pre_scale(scale_factor);
+			},
+		}
+	}
+
+	/// glyph outlines of a text mask, [`Option::None`] for other masks.
+	pub fn to_outlines(&self) -> Option<Vec<Outline>> {
+		match self {
+			ShapeMask::Text(t) => Some(t.to_outlines().unwrap_or_default()),
+			ShapeMask::RichText(t) => Some(t.to_outlines().unwrap_or_default()),
+			ShapeMask::TextPath(t) => Some(t.to_outlines().unwrap_or_default()),
+			_ => None,
+		}
+	}
+
 	/// convert a shape into vertexs
 	#[cfg(feature = "vertexs")]
 	pub fn into_vertexs(&self, size: Vec2, style: &Style) -> (Vec<Vertex>, Vec<u32>, Area) {
@@ -471,14 +850,176 @@ impl ShapeMask {
 			},
 			ShapeMask::Polygon(t) => t.into_vertexs(style, size),
 			ShapeMask::Line(t) => t.into_vertexs(style, size),
+			ShapeMask::Text(_) | ShapeMask::RichText(_) | ShapeMask::TextPath(_) => convert_path(outlines_path(&self.to_outlines().unwrap_or_default()), style, size),
 		}
 	}
 }
 
+/// a [`ShapeMask`] used for clipping, only things inside every clip mask of a [`Style`] will show.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct ClipMask {
+	pub mask: ShapeMask,
+	/// in the same coordinate as [`Style::position`], for shapes inside a [`ShapeElement::Group`], this is relative to the group.
+	pub position: Vec2,
+}
+
+impl Default for ClipMask {
+	fn default() -> Self {
+		Self {
+			mask: ShapeMask::Rect(Rect::default()),
+			position: Vec2::ZERO,
+		}
+	}
+}
+
+impl ClipMask {
+	/// flatten current mask into a [`Polygon`] with `position` applied. a text gives its area, see [`Self::to_polygons`] for its outlines.
+	pub fn to_polygon(&self) -> Polygon {
+		fn arc(center: Vec2, radius: Vec2, start: f32, segments: usize) -> Vec<Vec2> {
+			(0..=segments).map(|i| {
+				let angle = start + PI / 2.0 * i as f32 / segments as f32;
+				center + Vec2::new(angle.cos(), angle.sin()) * radius
+			}).collect()
+		}
+		fn corners(area: Area) -> Vec<Vec2> {
+			vec!(area.left_top(), area.right_top(), area.right_bottom(), area.left_bottom())
+		}
+		let points = match &self.mask {
+			ShapeMask::Circle(t) => {
+				let segments = ((2.0 * PI * t.radius / 2.0) as usize).clamp(16, 256);
+				(0..segments).map(|i| t.sample(i as f32 / segments as f32) + Vec2::same(t.radius)).collect()
+			},
+			ShapeMask::Rect(t) => {
+				let size = t.width_and_height;
+				if t.rounding == Vec2::ZERO {
+					vec!(Vec2::ZERO, Vec2::x(size.x), size, Vec2::y(size.y))
+				}else {
+					let rounding = t.rounding;
+					let segments = ((PI * rounding.x.max(rounding.y) / 4.0) as usize).clamp(4, 64);
+					let mut points = arc(size - rounding, rounding, 0.0, segments);
+					points.append(&mut arc(Vec2::new(rounding.x, size.y - rounding.y), rounding, PI / 2.0, segments));
+					points.append(&mut arc(rounding, rounding, PI, segments));
+					points.append(&mut arc(Vec2::new(size.x - rounding.x, rounding.y), rounding, PI * 1.5, segments));
+					points
+				}
+			},
+			ShapeMask::Line(t) => vec!(Vec2::ZERO, *t),
+			ShapeMask::CubicBezier(t) => (0..=32).map(|i| t.sample(i as f32 / 32.0)).collect(),
+			ShapeMask::Polygon(t) => t.points.clone(),
+			ShapeMask::Text(t) => corners(t.try_area(&Style::default()).unwrap_or(Area::ZERO)),
+			ShapeMask::RichText(t) => corners(t.try_area(&Style::default()).unwrap_or(Area::ZERO)),
+			ShapeMask::TextPath(t) => corners(t.try_area(&Style::default()).unwrap_or(Area::ZERO)),
+		};
+		let mut polygon: Polygon = points.into();
+		polygon.move_to(self.position);
+		polygon
+	}
+
+	/// flatten current mask into polygons with `position` applied, every contour of a text is a polygon, filled in even-odd rule.
+	pub fn to_polygons(&self) -> Vec<Polygon> {
+		match self.mask.to_outlines() {
+			Some(outlines) => {
+				let mut polygons: Vec<Polygon> = outlines.iter().flat_map(|t| t.to_polygons(0.25)).collect();
+				for polygon in &mut polygons {
+					polygon.move_to(self.position);
+				}
+				polygons
+			},
+			None => vec!(self.to_polygon()),
+		}
+	}
+
+	/// check if this mask can be clipped on cpu.
+	pub fn is_convex(&self) -> bool {
+		match &self.mask {
+			ShapeMask::Circle(_) | ShapeMask::Rect(_) => true,
+			ShapeMask::Text(_) | ShapeMask::RichText(_) | ShapeMask::TextPath(_) => false,
+			_ => self.to_polygon().is_convex(),
+		}
+	}
+
+	/// check if a point is inside this mask.
+	pub fn is_point_inside(&self, point: Vec2) -> bool {
+		match &self.mask {
+			ShapeMask::Text(_) | ShapeMask::RichText(_) | ShapeMask::TextPath(_) => self.to_polygons().iter().filter(|t| t.is_point_inside(point)).count() % 2 == 1,
+			_ => self.to_polygon().is_point_inside(point),
+		}
+	}
+
+	/// move a mask to a new place
+	pub fn move_delta_to(&mut self, delta: Vec2) {
+		self.position = self.position + delta;
+	}
+
+	/// pre-scale current mask with (0,0), see more in [`Shape::pre_scale`]
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		self.position = self.position * scale_factor;
+		self.mask.pre_scale(scale_factor);
+	}
+
+	/// get svg element of this mask, used inside `<clipPath>`.
+	pub fn svg(&self) -> String {
+		let position = self.position;
+		match &self.mask {
+			ShapeMask::Circle(t) => format!("<circle cx=\"{}\" cy=\"{}\" r=\"{}\"/>", position.x + t.radius, position.y + t.radius, t.radius),
+			ShapeMask::Rect(t) => format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" ry=\"{}\"/>", 
+				position.x, 
+				position.y, 
+				t.width_and_height.x, 
+				t.width_and_height.y, 
+				t.rounding.x, 
+				t.rounding.y
+			),
+			ShapeMask::CubicBezier(t) => format!("<path d=\"M {} C {} {} {} Z\"/>",
+				(position + t.points[0]).svg(),
+				(position + t.points[1]).svg(),
+				(position + t.points[2]).svg(),
+				(position + t.points[3]).svg(),
+			),
+			ShapeMask::Line(_) | ShapeMask::Polygon(_) => {
+				let mut points = String::new();
+				for point in self.to_polygon() {
+					points = format!("{points} {}", point.svg())
+				}
+				format!("<polygon points=\"{}\"/>", points)
+			},
+			ShapeMask::Text(_) | ShapeMask::RichText(_) | ShapeMask::TextPath(_) => {
+				let mut d = String::new();
+				for mut outline in self.mask.to_outlines().unwrap_or_default() {
+					outline.move_delta_to(position);
+					d = format!("{d} {}", outline.svg());
+				}
+				format!("<path d=\"{}\" clip-rule=\"evenodd\"/>", d.trim())
+			},
+		}
+	}
+
+	/// convert this mask into vertexs, useful for drawing stencil.
+	#[cfg(feature = "vertexs")]
+	pub fn into_vertexs(&self, size: Vec2) -> (Vec<Vertex>, Vec<u32>) {
+		let style = Style {
+			position: self.position,
+			fill: Color::WHITE,
+			stroke_width: 0.0,
+			..Default::default()
+		};
+		let (vertexs, indices, _) = self.mask.into_vertexs(size, &style);
+		(vertexs, indices)
+	}
+}
+
 impl Shape {
-	/// convert a shape into vertexs
+	/// convert a shape into vertexs, convex clip masks are applied on cpu, see more in [`Self::into_draw_commands`] for others.
 	#[cfg(feature = "vertexs")]
 	pub fn into_vertexs(&self, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
+		let (vertexs, indices, clip) = self.unclipped_vertexs(size);
+		let (vertexs, indices) = self.style.cpu_clip(vertexs, indices, size);
+		(vertexs, indices, clip)
+	}
+
+	#[cfg(feature = "vertexs")]
+	fn unclipped_vertexs(&self, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
 		match &self.shape {
 			ShapeElement::Circle(t) => {
 				t.into_vertexs(&self.style, size)
@@ -490,12 +1031,243 @@ impl Shape {
 				t.into_vertexs(&self.style, size)
 			},
 			ShapeElement::Polygon(t) => t.into_vertexs(&self.style, size),
-			ShapeElement::Text(_) => unreachable!(),
-			ShapeElement::Image(_) => unreachable!(),
+			ShapeElement::Text(t) => t.into_vertexs(&self.style, size),
+			ShapeElement::RichText(t) => t.into_vertexs(&self.style, size),
+			ShapeElement::TextPath(t) => t.into_vertexs(&self.style, size),
+			ShapeElement::Image(t) => t.into_vertexs(&self.style, size),
 			ShapeElement::Line(t) => t.into_vertexs(&self.style, size),
+			ShapeElement::Group(shapes) => {
+				let mut vertexs = vec!();
+				let mut indices = vec!();
+				for shape in shapes {
+					let (mut shape_vertexs, shape_indices, _) = shape.into_vertexs(size);
+					let offset = vertexs.len() as u32;
+					vertexs.append(&mut shape_vertexs);
+					indices.extend(shape_indices.into_iter().map(|t| t + offset));
+				}
+				transform_vertexs(&mut vertexs, &self.style, size);
+				(vertexs, indices, normalize_clip(self.style.clip, size))
+			},
 		}
 	}
 
+	/// convert a shape into vertexs along with its render state.
+	///
+	/// a [`ShapeElement::Group`] gives commands of every shape inside, opacity multiplied.
+	/// clips of shapes inside are transformed by the group and then crossed with group's clip.
+	/// layer of the group wins, shapes inside are always drawn on group's layer.
+	/// blend mode of the group is used when shape inside uses [`BlendMode::Normal`].
+	#[cfg(feature = "vertexs")]
+	pub fn into_draw_commands(&self, size: Vec2) -> Vec<DrawCommand> {
+		let shapes = if let ShapeElement::Group(shapes) = &self.shape {
+			shapes
+		}else {
+			let (vertexs, indices, clip) = self.into_vertexs(size);
+			return vec!(DrawCommand {
+				vertexs,
+				indices,
+				clip,
+				layer: self.style.layer,
+				blend_mode: self.style.blend_mode,
+				opacity: self.style.opacity,
+				effects: self.effect_records(size),
+				stencil_masks: self.style.stencil_masks(size),
+				texture: match &self.shape {
+					ShapeElement::Image(t) => Some(t.id.clone()),
+					ShapeElement::Text(_) | ShapeElement::RichText(_) | ShapeElement::TextPath(_) => Some(GlyphAtlas::TEXTURE_ID.to_string()),
+					_ => None,
+				},
+			})
+		};
+		let clip = normalize_clip(self.style.clip, size);
+		let mut back = vec!();
+		if !self.style.effects.is_empty() {
+			// effects of the group itself, drawn before everything inside
+			back.push(DrawCommand {
+				clip,
+				layer: self.style.layer,
+				blend_mode: self.style.blend_mode,
+				opacity: self.style.opacity,
+				effects: self.effect_records(size),
+				stencil_masks: self.style.stencil_masks(size),
+				..Default::default()
+			});
+		}
+		let is_translation = self.style.rotate == 0.0 && self.style.size == Vec2::NOT_TO_SCALE;
+		for shape in shapes {
+			for mut command in shape.into_draw_commands(size) {
+				transform_vertexs(&mut command.vertexs, &self.style, size);
+				let (vertexs, indices) = self.style.cpu_clip(command.vertexs, command.indices, size);
+				command.vertexs = vertexs;
+				command.indices = indices;
+				for (vertexs, _) in &mut command.stencil_masks {
+					transform_vertexs(vertexs, &self.style, size);
+				}
+				command.stencil_masks.append(&mut self.style.stencil_masks(size));
+				for effect in &mut command.effects {
+					transform_vertexs(&mut effect.vertexs, &self.style, size);
+					effect.area = effect.area.transform(&self.style);
+					if !is_translation {
+						effect.rounded_rect = None;
+					}else if let Some((rect, _)) = &mut effect.rounded_rect {
+						rect.move_delta_to(self.style.position);
+						for vertex in &mut effect.vertexs {
+							vertex.uv = [vertex.uv[0] + self.style.position.x, vertex.uv[1] + self.style.position.y];
+						}
+					}
+				}
+				command.clip = self.transform_clip(command.clip, size).cross_part(&clip);
+				command.layer = self.style.layer;
+				command.opacity *= self.style.opacity;
+				if command.blend_mode == BlendMode::Normal {
+					command.blend_mode = self.style.blend_mode;
+				}
+				back.push(command);
+			}
+		}
+		back
+	}
+
+	/// transform a normalized clip of a shape inside this group, gives bounding box of transformed clip.
+	#[cfg(feature = "vertexs")]
+	fn transform_clip(&self, clip: Area, size: Vec2) -> Area {
+		if !(clip.area[0].x.is_finite() && clip.area[0].y.is_finite() && clip.area[1].x.is_finite() && clip.area[1].y.is_finite()) {
+			return clip;
+		}
+		let pixels = Area::new((clip.area[0] + Vec2::same(1.0)) * size / 2.0, (clip.area[1] + Vec2::same(1.0)) * size / 2.0);
+		normalize_clip(pixels.transform(&self.style), size)
+	}
+
+	/// convert a shape into svg
+	pub fn into_svg(&self) -> String {
+		match &self.shape {
+			ShapeElement::Circle(t) => t.into_svg(&self.style),
+			ShapeElement::Rect(t) => t.into_svg(&self.style),
+			ShapeElement::CubicBezier(t) => t.into_svg(&self.style),
+			ShapeElement::Polygon(t) => t.into_svg(&self.style),
+			ShapeElement::Text(t) => t.into_svg(&self.style),
+			ShapeElement::RichText(t) => t.into_svg(&self.style),
+			ShapeElement::TextPath(t) => t.into_svg(&self.style),
+			ShapeElement::Image(t) => t.into_svg(&self.style),
+			ShapeElement::Line(t) => t.into_svg(&self.style),
+			ShapeElement::Group(shapes) => {
+				let mut inner = String::new();
+				for shape in shapes {
+					inner = inner + &shape.into_svg();
+				}
+				self.style.svg_wrap(format!("<g transform=\"{}\" opacity=\"{}\" style=\"mix-blend-mode: {}\">{}</g>",
+					self.style.svg_transform(),
+					self.style.opacity,
+					self.style.blend_mode.svg(),
+					inner
+				))
+			},
+		}
+	}
+
+	/// convert a shape into svg, images known by `registry` get their intrinsic sizes and are embedded as data uri if pixels are known.
+	pub fn into_svg_with_images(&self, registry: &ImageRegistry) -> String {
+		let mut shape = self.clone();
+		shape.resolve_images(registry, true);
+		shape.into_svg()
+	}
+
+	/// fill unknown intrinsic sizes of images in this shape, see more in [`ImageRegistry::resolve`].
+	pub fn resolve_images(&mut self, registry: &ImageRegistry, embed: bool) {
+		match &mut self.shape {
+			ShapeElement::Image(t) => { registry.resolve(t, embed); },
+			ShapeElement::Group(shapes) => {
+				for shape in shapes {
+					shape.resolve_images(registry, embed);
+				}
+			},
+			_ => {},
+		}
+	}
+
+	/// check if a point is inside this shape, only checks bounding box for shapes other than [`ShapeElement::Group`].
+	///
+	/// points outside clip masks are never inside.
+	pub fn is_point_inside(&self, point: Vec2) -> bool {
+		if !self.style.clip_masks.iter().all(|mask| mask.is_point_inside(point)) {
+			return false
+		}
+		match &self.shape {
+			ShapeElement::Group(shapes) => {
+				match self.style.inverse_transform_point(point) {
+					Some(point) => shapes.iter().any(|shape| shape.is_point_inside(point)),
+					None => false,
+				}
+			},
+			_ => self.get_area().is_point_inside(&point),
+		}
+	}
+
+	/// apply a animation value to this shape.
+	pub fn animate_to(&mut self, target: &StyleToAnimate, change_to: f32) {
+		match target {
+			StyleToAnimate::Style(id) => self.style.animate(*id, change_to),
+			StyleToAnimate::Id(id) => self.shape.animate(*id, change_to),
+		}
+	}
+
+	/// get renderer friendly records of effects, see more in [`EffectRecord`]
+	#[cfg(feature = "vertexs")]
+	pub fn effect_records(&self, size: Vec2) -> Vec<EffectRecord> {
+		let area = self.get_area();
+		let rounded_rect = if self.style.rotate == 0.0 {
+			match &self.shape {
+				ShapeElement::Rect(t) => Some((area, t.rounding * self.style.size)),
+				ShapeElement::Circle(t) => Some((area, self.style.size * t.radius)),
+				_ => None
+			}
+		}else {
+			None
+		};
+		self.style.effects.iter().map(|effect| {
+			let margin = effect.margin();
+			let effect_area = Area::new(area.area[0] - margin, area.area[1] + margin);
+			let (sigma, (vertexs, indices)) = match effect.shadow() {
+				None => (0.0, (vec!(), vec!())),
+				Some(shadow) => (shadow.blur / 2.0, if let Some((rect, _)) = rounded_rect {
+					let mut rect = rect;
+					rect.move_delta_to(shadow.offset);
+					let quad = Area::new(rect.area[0] - Vec2::same(shadow.blur * 1.5), rect.area[1] + Vec2::same(shadow.blur * 1.5));
+					let vertexs = quad.points().into_iter().map(|point| Vertex {
+						position: [point.x / size.x * 2.0 - 1.0, - (point.y / size.y * 2.0 - 1.0), 0.0],
+						color: shadow.color.normalized(),
+						uv: [point.x, point.y],
+					}).collect();
+					(vertexs, vec!(0, 1, 2, 0, 2, 3))
+				}else {
+					let silhouette = Shape {
+						style: Style {
+							// transform origin is relative to position, so it stays
+							position: self.style.position + shadow.offset,
+							fill: shadow.color,
+							fill_paint: None,
+							stroke_color: shadow.color,
+							stroke_paint: None,
+							effects: vec!(),
+							..self.style.clone()
+						},
+						shape: self.shape.clone(),
+					};
+					let (vertexs, indices, _) = silhouette.into_vertexs(size);
+					(vertexs, indices)
+				}),
+			};
+			EffectRecord {
+				effect: effect.clone(),
+				vertexs,
+				indices,
+				rounded_rect: if effect.shadow().is_some() { rounded_rect } else { None },
+				sigma,
+				area: effect_area,
+			}
+		}).collect()
+	}
+
 	/// pre-scale current shape with (0,0), useful when scaling svg.
 	pub fn pre_scale(&mut self, scale_factor: f32) {
 		self.style = Style {
@@ -503,8 +1275,20 @@ impl Shape {
 			transform_origin: self.style.transform_origin * scale_factor,
 			stroke_width: self.style.stroke_width * scale_factor,
 			clip: [self.style.clip.area[0] * scale_factor, self.style.clip.area[1] * scale_factor].into(),
-			..self.style
+			..self.style.clone()
 		};
+		for mask in &mut self.style.clip_masks {
+			mask.pre_scale(scale_factor);
+		}
+		if let Some(paint) = &mut self.style.fill_paint {
+			paint.pre_scale(scale_factor);
+		}
+		if let Some(paint) = &mut self.style.stroke_paint {
+			paint.pre_scale(scale_factor);
+		}
+		for effect in &mut self.style.effects {
+			effect.pre_scale(scale_factor);
+		}
 		match &mut self.shape {
 			ShapeElement::Circle(t) => {
 				t.radius = t.radius * scale_factor;
@@ -523,16 +1307,29 @@ impl Shape {
 					*point = *point * scale_factor;
 				}
 			},
-			ShapeElement::Text(_) => {
-				// not a good idea
-				self.style.size = self.style.size * scale_factor;
+			ShapeElement::Text(t) => {
+				t.pre_scale(scale_factor);
+			},
+			ShapeElement::RichText(t) => {
+				t.pre_scale(scale_factor);
+			},
+			ShapeElement::TextPath(t) => {
+				t.pre_scale(scale_factor);
 			},
 			ShapeElement::Image(t) => {
-				t.size = t.size * scale_factor
+				t.size = t.size * scale_factor;
+				if let Some(mask) = &mut t.mask {
+					mask.pre_scale(scale_factor);
+				}
 			},
 			ShapeElement::Line(t1) => {
 				*t1 = *t1 * scale_factor;
 			},
+			ShapeElement::Group(shapes) => {
+				for shape in shapes {
+					shape.pre_scale(scale_factor);
+				}
+			},
 		}
 	}
 
@@ -551,6 +1348,12 @@ impl Shape {
 			ShapeElement::Text(t) => {
 				t.get_area(&self.style)
 			},
+			ShapeElement::RichText(t) => {
+				t.get_area(&self.style)
+			},
+			ShapeElement::TextPath(t) => {
+				t.get_area(&self.style)
+			},
 			ShapeElement::Polygon(t) => {
 				t.get_area(&self.style)
 			},
@@ -560,12 +1363,22 @@ impl Shape {
 			ShapeElement::Line(t1) => {
 				Area::new(self.style.position, *t1).transform(&self.style)
 			},
+			ShapeElement::Group(shapes) => {
+				let mut area = Area::ZERO;
+				for shape in shapes {
+					area.combine(&shape.get_area());
+				}
+				area.transform(&self.style)
+			},
 		}
 	}
 
 	/// move a shape to a new place
 	pub fn move_delta_to(&mut self, delta: Vec2) {
 		self.style.clip.move_delta_to(delta);
+		for mask in &mut self.style.clip_masks {
+			mask.move_delta_to(delta);
+		}
 		self.style.transform_origin = self.style.transform_origin + delta;
 		self.style.position = self.style.position + delta;
 		if let ShapeElement::Polygon(t) = &mut self.shape {
@@ -574,4 +1387,251 @@ impl Shape {
 			}
 		}
 	}
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn line_cap_and_join_in_svg() {
+		let shape = Shape {
+			style: Style {
+				line_cap: LineCap::Round,
+				line_join: LineJoin::Bevel,
+				miter_limit: 2.0,
+				..Default::default()
+			},
+			shape: ShapeElement::Line(Vec2::new(100.0, 0.0)),
+		};
+		let svg = shape.into_svg();
+		assert!(svg.contains("stroke-linecap=\"round\"") && svg.contains("stroke-linejoin=\"bevel\"") && svg.contains("stroke-miterlimit=\"2\""), "{svg}");
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn square_cap_extends_line() {
+		let size = Vec2::same(400.0);
+		let width = |line_cap: LineCap| {
+			let shape = Shape {
+				style: Style {
+					position: Vec2::same(100.0),
+					stroke_width: 10.0,
+					stroke_color: Color::BLACK,
+					line_cap,
+					..Default::default()
+				},
+				shape: ShapeElement::Line(Vec2::new(100.0, 0.0)),
+			};
+			bounds(&shape.into_vertexs(size).0).width() / 2.0 * size.x
+		};
+		assert!((width(LineCap::Butt) - 100.0).abs() < 0.1, "{}", width(LineCap::Butt));
+		assert!((width(LineCap::Square) - 110.0).abs() < 0.1, "{}", width(LineCap::Square));
+	}
+
+	#[test]
+	fn animation_ids_do_not_move_with_paints() {
+		let mut shape = Shape {
+			shape: ShapeElement::Circle(Circle { radius: 1.0 }),
+			..Default::default()
+		};
+		let before = shape.animate_len();
+		shape.style.fill_paint = Some(Paint::Solid(Color::WHITE));
+		assert_eq!(shape.animate_len(), before + 4);
+		shape.animate(17, 0.5);
+		shape.animate(Style::FIXED_LEN, 5.0);
+		// alpha of the solid fill paint comes after the circle
+		shape.animate(Style::FIXED_LEN + 1 + 3, 0.0);
+		assert_eq!(shape.style.opacity, 0.5);
+		assert_eq!(shape.shape, ShapeElement::Circle(Circle { radius: 5.0 }));
+		assert_eq!(shape.style.fill_paint, Some(Paint::Solid([255, 255, 255, 0].into())));
+	}
+
+	#[cfg(feature = "vertexs")]
+	fn bounds(vertexs: &[Vertex]) -> Area {
+		// `Area::combine` skips empty areas, so points are collected by hand
+		let mut area = Area { area: [Vec2::INF, Vec2::NEG_INF] };
+		for vertex in vertexs {
+			area.area[0] = Vec2::new(area.area[0].x.min(vertex.position[0]), area.area[0].y.min(vertex.position[1]));
+			area.area[1] = Vec2::new(area.area[1].x.max(vertex.position[0]), area.area[1].y.max(vertex.position[1]));
+		}
+		area
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn drop_shadow_is_offset_the_same_when_rotated() {
+		let size = Vec2::same(200.0);
+		let offset = Vec2::new(10.0, 20.0);
+		for rotate in [0.0, 1.0] {
+			let shape = Shape {
+				style: Style {
+					position: Vec2::same(50.0),
+					transform_origin: Vec2::same(20.0),
+					rotate,
+					size: Vec2::new(2.0, 1.0),
+					clip: Area::new(Vec2::ZERO, size),
+					effects: vec!(Effect::DropShadow(DropShadow { offset, ..Default::default() })),
+					..Default::default()
+				},
+				shape: ShapeElement::Polygon(vec!(Vec2::ZERO, Vec2::new(40.0, 0.0), Vec2::new(0.0, 30.0)).into()),
+			};
+			let (vertexs, _, _) = shape.into_vertexs(size);
+			let shadow = &shape.effect_records(size)[0];
+			let (shape_area, shadow_area) = (bounds(&vertexs), bounds(&shadow.vertexs));
+			let delta = shadow_area.area[0] - shape_area.area[0];
+			// normalized, y goes up
+			assert!((delta.x - offset.x / size.x * 2.0).abs() < 1e-4, "{rotate}: {delta:?}");
+			assert!((delta.y + offset.y / size.y * 2.0).abs() < 1e-4, "{rotate}: {delta:?}");
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn draw_list_batches_by_blend_mode_and_opacity() {
+		let size = Vec2::same(200.0);
+		let mut painter = Painter::default();
+		painter.set_clip(Area::new(Vec2::ZERO, size));
+		painter.rect(Vec2::same(10.0), Vec2::ZERO);
+		painter.rect(Vec2::same(10.0), Vec2::ZERO);
+		painter.set_blend_mode(BlendMode::Multiply);
+		painter.rect(Vec2::same(10.0), Vec2::ZERO);
+		painter.set_opacity(0.5);
+		painter.rect(Vec2::same(10.0), Vec2::ZERO);
+		let commands = painter.draw_list(size);
+		assert_eq!(commands.len(), 3);
+		assert_eq!(commands[0].indices.len(), 2 * commands[1].indices.len());
+		assert_eq!((commands[1].blend_mode, commands[1].opacity), (BlendMode::Multiply, 1.0));
+		assert_eq!((commands[2].blend_mode, commands[2].opacity), (BlendMode::Multiply, 0.5));
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn group_opacity_multiplies_and_blend_mode_is_inherited() {
+		let size = Vec2::same(200.0);
+		let child = Shape {
+			style: Style { opacity: 0.5, clip: Area::new(Vec2::ZERO, size), ..Default::default() },
+			shape: ShapeElement::Rect(Rect { width_and_height: Vec2::same(10.0), ..Default::default() }),
+		};
+		let group = Shape {
+			style: Style { opacity: 0.5, blend_mode: BlendMode::Screen, clip: Area::new(Vec2::ZERO, size), ..Default::default() },
+			shape: ShapeElement::Group(vec!(child)),
+		};
+		let commands = group.into_draw_commands(size);
+		assert_eq!(commands.len(), 1);
+		assert_eq!((commands[0].blend_mode, commands[0].opacity), (BlendMode::Screen, 0.25));
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn rect_shadow_records_and_blur() {
+		let size = Vec2::same(200.0);
+		let shape = Shape {
+			style: Style {
+				position: Vec2::same(20.0),
+				clip: Area::new(Vec2::ZERO, size),
+				effects: vec!(Effect::DropShadow(DropShadow { offset: Vec2::new(5.0, 0.0), blur: 4.0, ..Default::default() }), Effect::GaussianBlur(2.0)),
+				..Default::default()
+			},
+			shape: ShapeElement::Rect(Rect { width_and_height: Vec2::same(10.0), rounding: Vec2::same(2.0) }),
+		};
+		let records = shape.effect_records(size);
+		assert_eq!(records[0].rounded_rect, Some((Area::new(Vec2::same(20.0), Vec2::same(30.0)), Vec2::same(2.0))));
+		assert_eq!(records[0].sigma, 2.0);
+		// a quad large enough for the blur, uv in pixels
+		assert_eq!(records[0].vertexs[0].uv, [25.0 - 6.0, 20.0 - 6.0]);
+		assert!(records[1].vertexs.is_empty() && records[1].rounded_rect.is_none());
+		let svg = shape.into_svg();
+		assert!(svg.contains("<feOffset in=\"blur_0\" dx=\"5\" dy=\"0\"") && svg.contains("<feGaussianBlur in=\"SourceGraphic\" stdDeviation=\"1\""), "{svg}");
+		// commands with effects are never merged
+		let mut painter = Painter::default();
+		painter.set_clip(Area::new(Vec2::ZERO, size));
+		painter.push(shape.clone());
+		painter.push(shape);
+		assert_eq!(painter.draw_list(size).len(), 2);
+	}
+
+	#[test]
+	fn transform_origin_is_relative_to_position() {
+		let style = Style {
+			position: Vec2::same(100.0),
+			transform_origin: Vec2::new(10.0, 0.0),
+			rotate: PI / 2.0,
+			..Default::default()
+		};
+		assert!((style.transform_point(Vec2::new(10.0, 0.0)) - Vec2::new(110.0, 100.0)).len() < 1e-4);
+		assert!((style.transform_point(Vec2::new(20.0, 0.0)) - Vec2::new(110.0, 110.0)).len() < 1e-4);
+		let back = style.inverse_transform_point(Vec2::new(110.0, 110.0)).unwrap();
+		assert!((back - Vec2::new(20.0, 0.0)).len() < 1e-4, "{back:?}");
+	}
+
+	#[test]
+	fn nested_groups_hit_test_through_transforms() {
+		let rect = Shape {
+			style: Style { position: Vec2::new(10.0, 0.0), ..Default::default() },
+			shape: ShapeElement::Rect(Rect { width_and_height: Vec2::same(10.0), ..Default::default() }),
+		};
+		let inner = Shape {
+			style: Style { size: Vec2::same(2.0), ..Default::default() },
+			shape: ShapeElement::Group(vec!(rect)),
+		};
+		let outer = Shape {
+			style: Style { position: Vec2::new(100.0, 0.0), ..Default::default() },
+			shape: ShapeElement::Group(vec!(inner)),
+		};
+		// scaled twice as large by the inner group and then moved by the outer one
+		assert!(outer.is_point_inside(Vec2::new(135.0, 15.0)));
+		assert!(!outer.is_point_inside(Vec2::new(115.0, 5.0)));
+		let svg = outer.into_svg();
+		assert_eq!(svg.matches("<g transform=").count(), 2, "{svg}");
+	}
+
+	#[test]
+	fn intersect_clip_nests_and_set_clip_replaces() {
+		let mut painter = Painter::default();
+		painter.intersect_clip(Area::new(Vec2::ZERO, Vec2::same(100.0)));
+		assert_eq!(painter.style.clip, Area::new(Vec2::ZERO, Vec2::same(100.0)));
+		painter.intersect_clip(Area::new(Vec2::same(50.0), Vec2::same(150.0)));
+		assert_eq!(painter.style.clip, Area::new(Vec2::same(50.0), Vec2::same(100.0)));
+		painter.set_clip(Area::new(Vec2::ZERO, Vec2::same(300.0)));
+		assert_eq!(painter.style.clip, Area::new(Vec2::ZERO, Vec2::same(300.0)));
+	}
+
+	#[test]
+	fn sprite_frame_ids() {
+		let mut shape = Shape {
+			shape: ShapeElement::Image(Image { sprite: Some(SpriteSheet::new(4, 4)), ..Default::default() }),
+			..Default::default()
+		};
+		let frame = |shape: &Shape| match &shape.shape {
+			ShapeElement::Image(image) => image.sprite.unwrap().frame,
+			_ => unreachable!(),
+		};
+		shape.shape.animate(Image::FRAME_ID, 2.5);
+		assert_eq!(frame(&shape), 2);
+		shape.animate(Style::FIXED_LEN + Image::FRAME_ID, 3.0);
+		assert_eq!(frame(&shape), 3);
+	}
+
+	#[test]
+	fn svg_clip_masks_and_effects_are_outside_transform() {
+		let mut shape = Shape {
+			style: Style {
+				rotate: 1.0,
+				..Default::default()
+			},
+			shape: ShapeElement::Rect(Rect { width_and_height: Vec2::same(10.0), ..Default::default() }),
+		};
+		let svg = shape.into_svg();
+		assert!(!svg.contains("none\""), "{svg}");
+		assert!(svg.starts_with("<rect"), "{svg}");
+		shape.style.clip_masks.push(ClipMask::default());
+		shape.style.effects.push(Effect::DropShadow(DropShadow::default()));
+		let svg = shape.into_svg();
+		let wrapper = &svg[svg.find("<g clip-path=").unwrap()..];
+		let rect = wrapper.find("<rect").unwrap();
+		assert!(wrapper[..rect].contains("filter=") && !wrapper[..rect].contains("transform="), "{svg}");
+		assert!(wrapper[rect..].contains("transform="), "{svg}");
+		shape.shape = ShapeElement::Line(Vec2::same(10.0));
+		assert!(shape.into_svg().contains("\"><path d="), "{}", shape.into_svg());
+	}
+}
@@ -0,0 +1,1000 @@
+//! Provid registries of resources used by shapes, such as images and fonts, and caches built from them.
+
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::hash::Hasher;
+use std::ops::Range;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::RwLock;
+use std::sync::RwLockReadGuard;
+use std::sync::RwLockWriteGuard;
+use fontdue::Font;
+use fontdue::layout::Layout;
+use once_cell::sync::Lazy;
+use crate::math::Vec2;
+use crate::shape::shape_elements::Color;
+use crate::shape::shape_elements::Image;
+use crate::shape::shape_elements::TextLayout;
+use crate::shape::shape_elements::TextStyle;
+#[cfg(feature = "default-font")]
+use crate::shape::shape_elements::DEFAULT_FONT;
+
+/// a image known by [`ImageRegistry`]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ImageAsset {
+	/// how large the image actually is in pixels
+	pub size: Vec2,
+	/// rgba8 pixels, row by row from top left. [`Option::None`] if only size is known.
+	pub pixels: Option<Arc<Vec<u8>>>,
+}
+
+impl ImageAsset {
+	/// get color of pixel at (x, y), [`Option::None`] if pixels are unknown or out of range.
+	pub fn pixel(&self, x: u32, y: u32) -> Option<Color> {
+		let pixels = self.pixels.as_ref()?;
+		if x as f32 >= self.size.x || y as f32 >= self.size.y {
+			return None
+		}
+		let index = (y as usize * self.size.x as usize + x as usize) * 4;
+		Some(Color::from([pixels[index], pixels[index + 1], pixels[index + 2], pixels[index + 3]]))
+	}
+
+	/// sample color at texture coordinate in [0..=1] with nearest filter, coordinates outside are clamped.
+	pub fn sample(&self, uv: Vec2) -> Option<Color> {
+		if self.size.x < 1.0 || self.size.y < 1.0 {
+			return None
+		}
+		let x = (uv.x * self.size.x).floor().clamp(0.0, self.size.x - 1.0);
+		let y = (uv.y * self.size.y).floor().clamp(0.0, self.size.y - 1.0);
+		self.pixel(x as u32, y as u32)
+	}
+
+	/// encode pixels into a `data:image/png;base64,` uri, used in svg exporting.
+	pub fn data_uri(&self) -> Option<String> {
+		let pixels = self.pixels.as_ref()?;
+		Some(format!("data:image/png;base64,{}", base64(&encode_png(self.size.x as u32, self.size.y as u32, pixels))))
+	}
+}
+
+/// errors when adding images into [`ImageRegistry`]
+#[derive(Clone, Debug, PartialEq)]
+pub enum ImageError {
+	/// the bytes are neither ppm nor qoi
+	UnknownFormat,
+	/// the bytes ended before the whole image is read
+	Truncated,
+	/// length of pixels does not match `width * height * 4`
+	SizeMismatch { expected: usize, found: usize },
+	/// the header is broken
+	InvalidHeader(&'static str),
+	/// width or height is 0, such images can not be drawn or encoded
+	Empty,
+}
+
+impl std::fmt::Display for ImageError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::UnknownFormat => write!(f, "unknown image format, only ppm and qoi are supported"),
+			Self::Truncated => write!(f, "image data is truncated"),
+			Self::SizeMismatch { expected, found } => write!(f, "expected {} bytes of rgba pixels, found {}", expected, found),
+			Self::InvalidHeader(reason) => write!(f, "invalid image header: {}", reason),
+			Self::Empty => write!(f, "image should not be empty"),
+		}
+	}
+}
+
+impl std::error::Error for ImageError {}
+
+/// maps [`Image::id`] to intrinsic sizes and pixels, so we can know how large a image is or export it.
+///
+/// # Example
+/// ```
+/// # use nablo_shape::prelude::*;
+/// # use nablo_shape::prelude::registry::ImageRegistry;
+/// # use nablo_shape::prelude::shape_elements::Color;
+/// let mut registry = ImageRegistry::new();
+/// registry.insert_encoded("dot", b"P3 1 1 255 255 0 0").unwrap();
+/// assert_eq!(registry.size_of("dot"), Some(Vec2::same(1.0)));
+/// assert_eq!(registry.get("dot").unwrap().pixel(0, 0), Some(Color::from([255, 0, 0, 255])));
+/// ```
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct ImageRegistry {
+	images: HashMap<String, ImageAsset>,
+}
+
+impl ImageRegistry {
+	/// create a empty registry
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// register a image by its size only, pixels are provided by renderers somewhere else.
+	pub fn insert_size(&mut self, id: impl Into<String>, size: Vec2) {
+		self.images.insert(id.into(), ImageAsset { size, pixels: None });
+	}
+
+	/// register a image with raw rgba8 pixels
+	pub fn insert_rgba(&mut self, id: impl Into<String>, width: u32, height: u32, pixels: Vec<u8>) -> Result<(), ImageError> {
+		if width == 0 || height == 0 {
+			return Err(ImageError::Empty)
+		}
+		let expected = width as usize * height as usize * 4;
+		if pixels.len() != expected {
+			return Err(ImageError::SizeMismatch { expected, found: pixels.len() })
+		}
+		self.images.insert(id.into(), ImageAsset { size: Vec2::new(width as f32, height as f32), pixels: Some(Arc::new(pixels)) });
+		Ok(())
+	}
+
+	/// decode a ppm (`P3` or `P6`) or qoi image and register it
+	pub fn insert_encoded(&mut self, id: impl Into<String>, bytes: &[u8]) -> Result<(), ImageError> {
+		let (width, height, pixels) = if bytes.starts_with(b"qoif") {
+			decode_qoi(bytes)?
+		}else if bytes.starts_with(b"P3") || bytes.starts_with(b"P6") {
+			decode_ppm(bytes)?
+		}else {
+			return Err(ImageError::UnknownFormat)
+		};
+		self.insert_rgba(id, width, height, pixels)
+	}
+
+	/// remove a image, returns the removed one
+	pub fn remove(&mut self, id: &str) -> Option<ImageAsset> {
+		self.images.remove(id)
+	}
+
+	/// check if a image is registered
+	pub fn contains(&self, id: &str) -> bool {
+		self.images.contains_key(id)
+	}
+
+	/// get a registered image
+	pub fn get(&self, id: &str) -> Option<&ImageAsset> {
+		self.images.get(id)
+	}
+
+	/// get intrinsic size of a registered image
+	pub fn size_of(&self, id: &str) -> Option<Vec2> {
+		self.images.get(id).map(|t| t.size)
+	}
+
+	/// get pixels of a registered image
+	pub fn pixels(&self, id: &str) -> Option<&[u8]> {
+		self.images.get(id).and_then(|t| t.pixels.as_ref()).map(|t| t.as_slice())
+	}
+
+	/// all registered ids
+	pub fn ids(&self) -> impl Iterator<Item = &String> {
+		self.images.keys()
+	}
+
+	/// fill unknown `intrinsic_size` of a image, also replace its id by a data uri if `embed` is true and pixels are known.
+	///
+	/// returns false if the image is not registered.
+	pub fn resolve(&self, image: &mut Image, embed: bool) -> bool {
+		let asset = match self.images.get(&image.id) {
+			Some(t) => t,
+			None => return false,
+		};
+		if image.intrinsic_size.is_none() {
+			image.intrinsic_size = Some(asset.size);
+		}
+		if embed {
+			if let Some(uri) = asset.data_uri() {
+				image.id = uri;
+			}
+		}
+		true
+	}
+}
+
+/// decoders never reserve more bytes than this up front, larger images grow while decoding
+const MAX_PREALLOCATE: usize = 1 << 24;
+
+fn decode_ppm(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), ImageError> {
+	let is_binary = bytes[1] == b'6';
+	let mut cursor = 2;
+	// reads a decimal number, skipping whitespaces and comments
+	let next_number = |cursor: &mut usize| -> Result<u32, ImageError> {
+		loop {
+			match bytes.get(*cursor) {
+				Some(b'#') => while !matches!(bytes.get(*cursor), Some(b'\n') | None) { *cursor += 1 },
+				Some(t) if t.is_ascii_whitespace() => *cursor += 1,
+				Some(_) => break,
+				None => return Err(ImageError::Truncated),
+			}
+		}
+		let start = *cursor;
+		while bytes.get(*cursor).is_some_and(|t| t.is_ascii_digit()) {
+			*cursor += 1;
+		}
+		std::str::from_utf8(&bytes[start..*cursor]).ok().and_then(|t| t.parse().ok()).ok_or(ImageError::InvalidHeader("expected a number"))
+	};
+	let width = next_number(&mut cursor)?;
+	let height = next_number(&mut cursor)?;
+	let max_value = next_number(&mut cursor)?;
+	if width == 0 || height == 0 {
+		return Err(ImageError::Empty)
+	}
+	if max_value == 0 || max_value > 65535 {
+		return Err(ImageError::InvalidHeader("max value should be in 1..=65535"))
+	}
+	let count = (width as usize).checked_mul(height as usize).and_then(|t| t.checked_mul(3)).ok_or(ImageError::InvalidHeader("image is too large"))?;
+	// exactly one whitespace after header for binary, at least one byte for every sample for ascii
+	let sample_len = if !is_binary { 1 } else if max_value > 255 { 2 } else { 1 };
+	let rest = bytes.len().saturating_sub(cursor + is_binary as usize);
+	if count.checked_mul(sample_len).is_none_or(|t| t > rest) {
+		return Err(ImageError::InvalidHeader("image is larger than its data"))
+	}
+	let mut samples = Vec::with_capacity(count.min(MAX_PREALLOCATE));
+	if is_binary {
+		cursor += 1;
+		let data = bytes.get(cursor..cursor + count * sample_len).ok_or(ImageError::Truncated)?;
+		if sample_len == 2 {
+			samples.extend(data.chunks(2).map(|t| u16::from_be_bytes([t[0], t[1]]) as u32));
+		}else {
+			samples.extend(data.iter().map(|t| *t as u32));
+		}
+	}else {
+		for _ in 0..count {
+			samples.push(next_number(&mut cursor)?);
+		}
+	}
+	let mut pixels = Vec::with_capacity((count / 3 * 4).min(MAX_PREALLOCATE));
+	for rgb in samples.chunks(3) {
+		pixels.extend(rgb.iter().map(|t| (t.min(&max_value) * 255 / max_value) as u8));
+		pixels.push(255);
+	}
+	Ok((width, height, pixels))
+}
+
+fn decode_qoi(bytes: &[u8]) -> Result<(u32, u32, Vec<u8>), ImageError> {
+	let header = bytes.get(..14).ok_or(ImageError::Truncated)?;
+	let width = u32::from_be_bytes([header[4], header[5], header[6], header[7]]);
+	let height = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+	if width == 0 || height == 0 {
+		return Err(ImageError::Empty)
+	}
+	// a single byte gives at most 62 pixels
+	let count = (width as usize).checked_mul(height as usize).filter(|t| t.checked_mul(4).is_some()).ok_or(ImageError::InvalidHeader("image is too large"))?;
+	if count.div_ceil(62) > bytes.len() - 14 {
+		return Err(ImageError::InvalidHeader("image is larger than its data"))
+	}
+	let mut pixels = Vec::with_capacity((count * 4).min(MAX_PREALLOCATE));
+	let mut seen = [[0u8; 4]; 64];
+	let mut current = [0, 0, 0, 255u8];
+	let mut cursor = 14;
+	let next = |cursor: &mut usize| -> Result<u8, ImageError> {
+		let back = *bytes.get(*cursor).ok_or(ImageError::Truncated)?;
+		*cursor += 1;
+		Ok(back)
+	};
+	while pixels.len() < count * 4 {
+		let tag = next(&mut cursor)?;
+		let mut run = 1;
+		match tag {
+			0xfe => {
+				current[0] = next(&mut cursor)?;
+				current[1] = next(&mut cursor)?;
+				current[2] = next(&mut cursor)?;
+			},
+			0xff => {
+				current[0] = next(&mut cursor)?;
+				current[1] = next(&mut cursor)?;
+				current[2] = next(&mut cursor)?;
+				current[3] = next(&mut cursor)?;
+			},
+			_ => match tag >> 6 {
+				0 => current = seen[(tag & 0x3f) as usize],
+				1 => {
+					current[0] = current[0].wrapping_add((tag >> 4) & 0x03).wrapping_sub(2);
+					current[1] = current[1].wrapping_add((tag >> 2) & 0x03).wrapping_sub(2);
+					current[2] = current[2].wrapping_add(tag & 0x03).wrapping_sub(2);
+				},
+				2 => {
+					let green = (tag & 0x3f).wrapping_sub(32);
+					let second = next(&mut cursor)?;
+					current[0] = current[0].wrapping_add(green).wrapping_add(second >> 4).wrapping_sub(8);
+					current[1] = current[1].wrapping_add(green);
+					current[2] = current[2].wrapping_add(green).wrapping_add(second & 0x0f).wrapping_sub(8);
+				},
+				_ => run = (tag & 0x3f) as usize + 1,
+			},
+		}
+		let hash = (current[0] as usize * 3 + current[1] as usize * 5 + current[2] as usize * 7 + current[3] as usize * 11) % 64;
+		seen[hash] = current;
+		for _ in 0..run {
+			pixels.extend_from_slice(&current);
+		}
+	}
+	pixels.truncate(count * 4);
+	Ok((width, height, pixels))
+}
+
+/// encode rgba8 pixels into a uncompressed png, the image should not be empty, see [`ImageError::Empty`].
+fn encode_png(width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+	fn chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+		out.extend_from_slice(&(data.len() as u32).to_be_bytes());
+		let start = out.len();
+		out.extend_from_slice(kind);
+		out.extend_from_slice(data);
+		let crc = crc32(&out[start..]);
+		out.extend_from_slice(&crc.to_be_bytes());
+	}
+	let mut raw = Vec::with_capacity(pixels.len() + height as usize);
+	for row in pixels.chunks((width as usize * 4).max(1)) {
+		// no filter
+		raw.push(0);
+		raw.extend_from_slice(row);
+	}
+	// zlib stream with stored deflate blocks
+	let mut zlib = vec!(0x78, 0x01);
+	let blocks: Vec<&[u8]> = raw.chunks(65535).collect();
+	for (i, block) in blocks.iter().enumerate() {
+		zlib.push((i + 1 == blocks.len()) as u8);
+		zlib.extend_from_slice(&(block.len() as u16).to_le_bytes());
+		zlib.extend_from_slice(&(!(block.len() as u16)).to_le_bytes());
+		zlib.extend_from_slice(block);
+	}
+	zlib.extend_from_slice(&adler32(&raw).to_be_bytes());
+	let mut header = vec!();
+	header.extend_from_slice(&width.to_be_bytes());
+	header.extend_from_slice(&height.to_be_bytes());
+	// 8 bit rgba, no interlace
+	header.extend_from_slice(&[8, 6, 0, 0, 0]);
+	let mut out = vec!(0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n');
+	chunk(&mut out, b"IHDR", &header);
+	chunk(&mut out, b"IDAT", &zlib);
+	chunk(&mut out, b"IEND", &[]);
+	out
+}
+
+fn crc32(data: &[u8]) -> u32 {
+	let mut crc = 0xffffffffu32;
+	for byte in data {
+		crc ^= *byte as u32;
+		for _ in 0..8 {
+			crc = if crc & 1 == 1 { (crc >> 1) ^ 0xedb88320 } else { crc >> 1 };
+		}
+	}
+	!crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+	let (mut a, mut b) = (1u32, 0u32);
+	for byte in data {
+		a = (a + *byte as u32) % 65521;
+		b = (b + a) % 65521;
+	}
+	(b << 16) | a
+}
+
+fn base64(data: &[u8]) -> String {
+	const TABLE: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+	let mut out = String::with_capacity(data.len().div_ceil(3) * 4);
+	for group in data.chunks(3) {
+		let n = (group[0] as u32) << 16 | (*group.get(1).unwrap_or(&0) as u32) << 8 | *group.get(2).unwrap_or(&0) as u32;
+		for i in 0..4 {
+			if i <= group.len() {
+				out.push(TABLE[(n >> (18 - i * 6) & 0x3f) as usize] as char);
+			}else {
+				out.push('=');
+			}
+		}
+	}
+	out
+}
+
+/// the global fonts used by text layout, see more in [`FontRegistry::global`].
+///
+/// contains [`DEFAULT_FONT`] with feature `default-font`, otherwise empty until fonts are loaded.
+static FONTS: Lazy<RwLock<FontRegistry>> = Lazy::new(|| {
+	#[allow(unused_mut)]
+	let mut registry = FontRegistry::new();
+	#[cfg(feature = "default-font")]
+	if let Err(e) = registry.load(DEFAULT_FAMILY, DEFAULT_FONT as &[u8], FontRegistry::NORMAL, false) {
+		// leave it empty, text layout will report [`FontError::NoFont`]
+		registry.default_font_error = Some(e);
+	}
+	RwLock::new(registry)
+});
+
+/// family name of the default font in [`FontRegistry::global`]
+pub const DEFAULT_FAMILY: &str = "default";
+
+/// a font face known by [`FontRegistry`]
+#[derive(Clone)]
+pub struct FontFace {
+	pub family: String,
+	/// refer to css `font-weight`, 400 for normal and 700 for bold
+	pub weight: u16,
+	pub is_italic: bool,
+	pub font: Arc<Font>,
+	/// the font file, needed by outlines such as [`crate::shape::shape_elements::Text::to_outlines`]. [`Option::None`] if not kept.
+	pub data: Option<Arc<Vec<u8>>>,
+}
+
+impl std::fmt::Debug for FontFace {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("FontFace")
+			.field("family", &self.family)
+			.field("weight", &self.weight)
+			.field("is_italic", &self.is_italic)
+			.finish()
+	}
+}
+
+/// errors when loading or choosing fonts
+#[derive(Clone, Debug, PartialEq)]
+pub enum FontError {
+	/// the bytes can not be parsed as a font
+	Parse(&'static str),
+	/// there is no such family in [`FontRegistry`]
+	UnknownFamily(String),
+	/// there is no font to layout text with, load one with [`FontRegistry::global_mut`] or turn on feature `default-font`.
+	NoFont,
+}
+
+impl std::fmt::Display for FontError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::Parse(reason) => write!(f, "failed to parse font: {}", reason),
+			Self::UnknownFamily(family) => write!(f, "font family \"{}\" is not loaded", family),
+			Self::NoFont => write!(f, "no font is loaded, load one with `FontRegistry::global_mut` or turn on feature `default-font`"),
+		}
+	}
+}
+
+impl std::error::Error for FontError {}
+
+/// a collection of fonts grouped by family, used to choose faces for [`TextStyle`] and find fallbacks for missing glyphs.
+#[derive(Clone, Debug, Default)]
+pub struct FontRegistry {
+	faces: Vec<FontFace>,
+	/// used when [`TextStyle::font_family`] is [`Option::None`] or unknown, the first loaded family if [`Option::None`].
+	default_family: Option<String>,
+	/// families to search in order when a glyph is missing
+	fallbacks: Vec<String>,
+	/// why the embedded default font failed to load
+	default_font_error: Option<FontError>,
+	/// changes every time faces or families change, so [`LayoutCache`] knows old layouts are outdated
+	generation: u64,
+}
+
+impl FontRegistry {
+	/// font weight for normal text
+	pub const NORMAL: u16 = 400;
+	/// font weight for bold text
+	pub const BOLD: u16 = 700;
+
+	/// create a empty registry
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// get the registry used by text layout
+	pub fn global() -> RwLockReadGuard<'static, FontRegistry> {
+		FONTS.read().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// change the registry used by text layout, for example loading fonts.
+	///
+	/// Note: do not call [`Self::global`] or any text layout before the returned guard is dropped.
+	pub fn global_mut() -> RwLockWriteGuard<'static, FontRegistry> {
+		FONTS.write().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// parse a font and add it under `family`, will replace the face with same family, weight and style.
+	pub fn load(&mut self, family: impl Into<String>, bytes: &[u8], weight: u16, is_italic: bool) -> Result<(), FontError> {
+		let font = Font::from_bytes(bytes, Default::default()).map_err(FontError::Parse)?;
+		self.add(FontFace {
+			family: family.into(),
+			weight,
+			is_italic,
+			font: Arc::new(font),
+			data: Some(Arc::new(bytes.to_vec())),
+		});
+		Ok(())
+	}
+
+	/// add a parsed font face, will replace the face with same family, weight and style.
+	pub fn add(&mut self, face: FontFace) {
+		self.faces.retain(|t| !(t.family == face.family && t.weight == face.weight && t.is_italic == face.is_italic));
+		self.faces.push(face);
+		self.generation += 1;
+	}
+
+	/// remove every face of a family
+	pub fn remove_family(&mut self, family: &str) {
+		self.faces.retain(|t| t.family != family);
+		self.generation += 1;
+	}
+
+	/// check if there is any face of `family`
+	pub fn contains(&self, family: &str) -> bool {
+		self.faces.iter().any(|t| t.family == family)
+	}
+
+	/// all loaded faces
+	pub fn faces(&self) -> &[FontFace] {
+		&self.faces
+	}
+
+	/// find a font by [`fontdue::Font::file_hash`], such as [`crate::shape::shape_elements::LayoutGlyph::font_hash`].
+	pub fn font_by_hash(&self, hash: usize) -> Option<Arc<Font>> {
+		self.faces.iter().find(|t| t.font.file_hash() == hash).map(|t| t.font.clone())
+	}
+
+	/// find the font file by [`fontdue::Font::file_hash`], [`Option::None`] if not found or not kept.
+	pub fn data_by_hash(&self, hash: usize) -> Option<Arc<Vec<u8>>> {
+		self.faces.iter().find(|t| t.font.file_hash() == hash).and_then(|t| t.data.clone())
+	}
+
+	/// check if there is no font at all
+	pub fn is_empty(&self) -> bool {
+		self.faces.is_empty()
+	}
+
+	/// a number which changes every time fonts or families change
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// set which family to use when a text does not choose one
+	pub fn set_default_family(&mut self, family: impl Into<String>) -> Result<(), FontError> {
+		let family = family.into();
+		if !self.contains(&family) {
+			return Err(FontError::UnknownFamily(family))
+		}
+		self.default_family = Some(family);
+		self.generation += 1;
+		Ok(())
+	}
+
+	/// get which family to use when a text does not choose one
+	pub fn default_family(&self) -> Option<&str> {
+		match &self.default_family {
+			Some(t) if self.contains(t) => Some(t),
+			_ => self.faces.first().map(|t| t.family.as_str()),
+		}
+	}
+
+	/// set families to search in order when a glyph is missing, such as cjk or symbol fonts. unknown families are ignored.
+	pub fn set_fallbacks(&mut self, fallbacks: Vec<String>) {
+		self.fallbacks = fallbacks;
+		self.generation += 1;
+	}
+
+	/// get families to search in order when a glyph is missing
+	pub fn fallbacks(&self) -> &[String] {
+		&self.fallbacks
+	}
+
+	/// choose the nearest face in `family`, prefers same style and then nearest weight. uses default family when `family` is [`Option::None`] or unknown.
+	pub fn select(&self, family: Option<&str>, weight: u16, is_italic: bool) -> Option<&FontFace> {
+		let family = match family {
+			Some(t) if self.contains(t) => t,
+			_ => self.default_family()?,
+		};
+		self.select_in(family, weight, is_italic)
+	}
+
+	fn select_in(&self, family: &str, weight: u16, is_italic: bool) -> Option<&FontFace> {
+		self.faces.iter().filter(|t| t.family == family).min_by_key(|t| {
+			// heavier faces win ties for bold, lighter ones for normal, like css does
+			let direction = if (t.weight >= weight) == (weight >= Self::BOLD) { 0 } else { 1 };
+			(t.is_italic != is_italic, t.weight.abs_diff(weight), direction)
+		})
+	}
+
+	/// same as [`Self::chain`], but returns a error if there is no font at all.
+	pub fn try_chain(&self, text_style: &TextStyle) -> Result<Vec<Arc<Font>>, FontError> {
+		let back = self.chain(text_style);
+		if back.is_empty() {
+			return Err(self.default_font_error.clone().unwrap_or(FontError::NoFont))
+		}
+		Ok(back)
+	}
+
+	/// get fonts to layout a text with, the chosen face comes first and then fallbacks, see more in [`layout_append`].
+	pub fn chain(&self, text_style: &TextStyle) -> Vec<Arc<Font>> {
+		let weight = text_style.weight();
+		let mut back: Vec<Arc<Font>> = vec!();
+		let chosen = self.select(text_style.font_family.as_deref(), weight, text_style.is_italic);
+		let fallbacks = self.fallbacks.iter().filter_map(|family| self.select_in(family, weight, text_style.is_italic));
+		let default = self.default_family().and_then(|family| self.select_in(family, weight, text_style.is_italic));
+		for face in chosen.into_iter().chain(fallbacks).chain(default) {
+			if !back.iter().any(|t| Arc::ptr_eq(t, &face.font)) {
+				back.push(face.font.clone());
+			}
+		}
+		back
+	}
+}
+
+/// the global cache of text layouts, see more in [`LayoutCache::global`].
+static LAYOUTS: Lazy<Mutex<LayoutCache>> = Lazy::new(|| Mutex::new(LayoutCache::new(LayoutCache::DEFAULT_CAPACITY)));
+
+/// everything a text layout depends on
+#[derive(Clone, Debug)]
+pub(crate) struct LayoutKey {
+	pub(crate) text: String,
+	pub(crate) spans: Vec<(Range<usize>, TextStyle)>,
+	pub(crate) base: TextStyle,
+	pub(crate) text_width: Option<f32>,
+	pub(crate) text_height: Option<f32>,
+	pub(crate) generation: u64,
+}
+
+// floats are compared by bits, same as how they are hashed, so keys with nan still equal themselves.
+impl PartialEq for LayoutKey {
+	fn eq(&self, other: &Self) -> bool {
+		fn same_bits(a: Option<f32>, b: Option<f32>) -> bool {
+			a.map(f32::to_bits) == b.map(f32::to_bits)
+		}
+		fn same_style(a: &TextStyle, b: &TextStyle) -> bool {
+			// destructured so a new field can not be forgotten here
+			let TextStyle { is_bold, is_italic, font_family, font_size, line_height, letter_spacing, word_spacing, align, vertical_align, wrap, overflow, decoration, decoration_thickness, decoration_color } = a;
+			*is_bold == b.is_bold && *is_italic == b.is_italic && *font_family == b.font_family &&
+			same_bits(*font_size, b.font_size) && same_bits(*line_height, b.line_height) &&
+			letter_spacing.to_bits() == b.letter_spacing.to_bits() && word_spacing.to_bits() == b.word_spacing.to_bits() &&
+			*align == b.align && *vertical_align == b.vertical_align && *wrap == b.wrap && *overflow == b.overflow &&
+			*decoration == b.decoration && same_bits(*decoration_thickness, b.decoration_thickness) && *decoration_color == b.decoration_color
+		}
+		self.text == other.text &&
+		self.generation == other.generation &&
+		same_bits(self.text_width, other.text_width) &&
+		same_bits(self.text_height, other.text_height) &&
+		same_style(&self.base, &other.base) &&
+		self.spans.len() == other.spans.len() &&
+		self.spans.iter().zip(&other.spans).all(|(a, b)| a.0 == b.0 && same_style(&a.1, &b.1))
+	}
+}
+
+impl Eq for LayoutKey {}
+
+impl Hash for LayoutKey {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		// styles are left out, same text with different styles only shares a bucket
+		self.text.hash(state);
+		for (range, _) in &self.spans {
+			range.hash(state);
+		}
+		self.text_width.map(f32::to_bits).hash(state);
+		self.text_height.map(f32::to_bits).hash(state);
+		self.generation.hash(state);
+	}
+}
+
+/// how well [`LayoutCache`] works
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct LayoutCacheStats {
+	/// how many layouts are reused
+	pub hits: u64,
+	/// how many layouts are built
+	pub misses: u64,
+	/// how many layouts are thrown away to keep the cache under its capacity
+	pub evictions: u64,
+	/// how many layouts are kept now
+	pub len: usize,
+	pub capacity: usize,
+}
+
+impl LayoutCacheStats {
+	/// hits in all lookups, 0.0 if nothing is looked up yet
+	pub fn hit_rate(&self) -> f32 {
+		let total = self.hits + self.misses;
+		if total == 0 {
+			0.0
+		}else {
+			self.hits as f32 / total as f32
+		}
+	}
+}
+
+/// a bounded cache of text layouts, the least recently used one is thrown away when full.
+///
+/// [`crate::shape::shape_elements::Text`] and [`crate::shape::shape_elements::RichText`] look up [`Self::global`] before laying out,
+/// so measuring the same text again, for example culling in [`crate::shape::Painter::draw`], is cheap.
+/// layouts built with outdated fonts are never used, see [`FontRegistry::generation`].
+#[derive(Clone, Debug, Default)]
+pub struct LayoutCache {
+	entries: HashMap<LayoutKey, (TextLayout, u64)>,
+	capacity: usize,
+	/// increases every lookup, used to find the least recently used layout
+	clock: u64,
+	stats: LayoutCacheStats,
+}
+
+impl LayoutCache {
+	/// capacity of [`Self::global`] at beginning
+	pub const DEFAULT_CAPACITY: usize = 512;
+
+	/// create a empty cache keeping at most `capacity` layouts, 0 to cache nothing.
+	pub fn new(capacity: usize) -> Self {
+		Self {
+			capacity,
+			..Default::default()
+		}
+	}
+
+	/// get the cache used by text layout
+	///
+	/// Note: do not call this again or layout any text before the returned guard is dropped.
+	pub fn global() -> MutexGuard<'static, LayoutCache> {
+		LAYOUTS.lock().unwrap_or_else(|e| e.into_inner())
+	}
+
+	/// throw away every layout, stats are kept.
+	pub fn clear(&mut self) {
+		self.entries.clear();
+	}
+
+	/// set how many layouts can be kept, least recently used layouts are thrown away if there are too many.
+	pub fn set_capacity(&mut self, capacity: usize) {
+		self.capacity = capacity;
+		while self.entries.len() > self.capacity {
+			self.evict();
+		}
+	}
+
+	/// how many layouts can be kept
+	pub fn capacity(&self) -> usize {
+		self.capacity
+	}
+
+	/// how many layouts are kept
+	pub fn len(&self) -> usize {
+		self.entries.len()
+	}
+
+	/// check if there is no layout kept
+	pub fn is_empty(&self) -> bool {
+		self.entries.is_empty()
+	}
+
+	/// get hits, misses and evictions since created or [`Self::reset_stats`]
+	pub fn stats(&self) -> LayoutCacheStats {
+		LayoutCacheStats {
+			len: self.entries.len(),
+			capacity: self.capacity,
+			..self.stats
+		}
+	}
+
+	/// set hits, misses and evictions to 0
+	pub fn reset_stats(&mut self) {
+		self.stats = LayoutCacheStats::default();
+	}
+
+	pub(crate) fn get(&mut self, key: &LayoutKey) -> Option<TextLayout> {
+		self.clock += 1;
+		match self.entries.get_mut(key) {
+			Some((layout, last_used)) => {
+				*last_used = self.clock;
+				self.stats.hits += 1;
+				Some(layout.clone())
+			},
+			None => {
+				self.stats.misses += 1;
+				None
+			},
+		}
+	}
+
+	pub(crate) fn insert(&mut self, key: LayoutKey, layout: TextLayout) {
+		if self.capacity == 0 {
+			return;
+		}
+		while self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+			self.evict();
+		}
+		self.entries.insert(key, (layout, self.clock));
+	}
+
+	/// throw away the least recently used layout
+	fn evict(&mut self) {
+		let oldest = self.entries.iter().min_by_key(|(_, (_, last_used))| *last_used).map(|(key, _)| key.clone());
+		if let Some(key) = oldest {
+			self.entries.remove(&key);
+			self.stats.evictions += 1;
+		}
+	}
+}
+
+/// append `text` into `layout`, every character uses the first font in `fonts` which has its glyph.
+///
+/// whitespaces stay with the font before them so words are not broken into pieces.
+/// `user_data` of every glyph is where its run starts in `text`, add it to `byte_offset` to get offset in `text`.
+pub fn layout_append(layout: &mut Layout<usize>, fonts: &[Arc<Font>], text: &str, px: f32) {
+	let chain: Vec<usize> = (0..fonts.len()).collect();
+	append_run(layout, fonts, &chain, text, px, 0);
+}
+
+/// same as [`layout_append`], but only fonts in `chain` are used and `offset` is added to `user_data`.
+pub(crate) fn append_run(layout: &mut Layout<usize>, fonts: &[Arc<Font>], chain: &[usize], text: &str, px: f32, offset: usize) {
+	if chain.is_empty() {
+		return;
+	}
+	let mut start = 0;
+	let mut current = None;
+	for (index, character) in text.char_indices() {
+		if character.is_whitespace() || character.is_control() {
+			continue;
+		}
+		let font_index = *chain.iter().find(|t| fonts[**t].has_glyph(character)).unwrap_or(&chain[0]);
+		match current {
+			Some(t) if t != font_index => {
+				layout.append(fonts, &fontdue::layout::TextStyle::with_user_data(&text[start..index], px, t, offset + start));
+				start = index;
+			},
+			_ => {},
+		}
+		current = Some(font_index);
+	}
+	layout.append(fonts, &fontdue::layout::TextStyle::with_user_data(&text[start..], px, current.unwrap_or(chain[0]), offset + start));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn ppm_rejects_overflowing_size() {
+		let mut registry = ImageRegistry::new();
+		assert!(matches!(registry.insert_encoded("a", b"P6\n4000000000 4000000000\n255\n\x00"), Err(ImageError::InvalidHeader(_))));
+		assert!(matches!(registry.insert_encoded("a", b"P3\n4000000000 4000000000\n255\n0 0 0"), Err(ImageError::InvalidHeader(_))));
+	}
+
+	#[test]
+	fn ppm_rejects_size_larger_than_data() {
+		let mut registry = ImageRegistry::new();
+		assert!(matches!(registry.insert_encoded("a", b"P6\n100 100\n255\n\x00\x00\x00"), Err(ImageError::InvalidHeader(_))));
+		assert!(matches!(registry.insert_encoded("a", b"P6\n1 1\n65535\n\x00\x00\x00"), Err(ImageError::InvalidHeader(_))));
+	}
+
+	#[test]
+	fn empty_images_are_rejected() {
+		let mut registry = ImageRegistry::new();
+		assert!(matches!(registry.insert_encoded("a", b"P6 0 0 255\n"), Err(ImageError::Empty)));
+		assert!(matches!(registry.insert_encoded("a", b"qoif\x00\x00\x00\x00\x00\x00\x00\x01\x04\x00"), Err(ImageError::Empty)));
+		assert!(matches!(registry.insert_rgba("a", 0, 5, vec!()), Err(ImageError::Empty)));
+		assert!(registry.get("a").is_none());
+	}
+
+	#[test]
+	fn ppm_decodes() {
+		let mut registry = ImageRegistry::new();
+		assert_eq!(registry.insert_encoded("a", b"P6\n1 1\n255\n\xff\x00\x00"), Ok(()));
+		assert_eq!(registry.insert_encoded("b", b"P3\n1 1\n255\n255 0 0"), Ok(()));
+	}
+
+	#[test]
+	fn qoi_rejects_overflowing_size() {
+		let mut registry = ImageRegistry::new();
+		let mut bytes = b"qoif".to_vec();
+		bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+		bytes.extend_from_slice(&u32::MAX.to_be_bytes());
+		bytes.extend_from_slice(&[4, 0, 0xc0]);
+		assert!(matches!(registry.insert_encoded("a", &bytes), Err(ImageError::InvalidHeader(_))));
+	}
+
+	#[test]
+	fn qoi_rejects_size_larger_than_data() {
+		let mut registry = ImageRegistry::new();
+		let mut bytes = b"qoif".to_vec();
+		bytes.extend_from_slice(&1000u32.to_be_bytes());
+		bytes.extend_from_slice(&1000u32.to_be_bytes());
+		bytes.extend_from_slice(&[4, 0, 0xfd, 0xfd]);
+		assert!(matches!(registry.insert_encoded("a", &bytes), Err(ImageError::InvalidHeader(_))));
+	}
+
+	#[test]
+	fn qoi_decodes() {
+		let mut registry = ImageRegistry::new();
+		let mut bytes = b"qoif".to_vec();
+		bytes.extend_from_slice(&2u32.to_be_bytes());
+		bytes.extend_from_slice(&1u32.to_be_bytes());
+		bytes.extend_from_slice(&[4, 0, 0xfe, 255, 0, 0, 0xc0]);
+		assert_eq!(registry.insert_encoded("a", &bytes), Ok(()));
+	}
+
+	#[test]
+	fn font_registry_errors() {
+		let mut registry = FontRegistry::new();
+		assert!(registry.select(None, FontRegistry::NORMAL, false).is_none());
+		assert_eq!(registry.try_chain(&TextStyle::default()).unwrap_err(), FontError::NoFont);
+		assert!(matches!(registry.load("a", b"not a font", FontRegistry::NORMAL, false), Err(FontError::Parse(_))));
+		assert_eq!(registry.set_default_family("a"), Err(FontError::UnknownFamily(String::from("a"))));
+	}
+
+	#[cfg(feature = "default-font")]
+	fn face(font: &Font, family: &str, weight: u16, is_italic: bool) -> FontFace {
+		FontFace { family: family.to_string(), weight, is_italic, font: Arc::new(font.clone()), data: None }
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn font_selection_and_fallbacks() {
+		let font = Font::from_bytes(DEFAULT_FONT as &[u8], Default::default()).unwrap();
+		let mut registry = FontRegistry::new();
+		registry.add(face(&font, "a", 300, false));
+		registry.add(face(&font, "a", FontRegistry::BOLD, false));
+		registry.add(face(&font, "a", FontRegistry::NORMAL, true));
+		registry.add(face(&font, "b", FontRegistry::NORMAL, false));
+		let select = |registry: &FontRegistry, family, weight, is_italic| registry.select(family, weight, is_italic).map(|t| (t.family.clone(), t.weight, t.is_italic));
+		assert_eq!(select(&registry, Some("a"), 600, false), Some((String::from("a"), 700, false)));
+		assert_eq!(select(&registry, Some("a"), 400, false), Some((String::from("a"), 300, false)));
+		// style is preferred over weight
+		assert_eq!(select(&registry, Some("a"), 700, true), Some((String::from("a"), 400, true)));
+		// unknown families use the first loaded one
+		assert_eq!(select(&registry, Some("c"), 400, false), Some((String::from("a"), 300, false)));
+		registry.set_default_family("b").unwrap();
+		assert_eq!(select(&registry, None, 400, false), Some((String::from("b"), 400, false)));
+		registry.set_fallbacks(vec!(String::from("c"), String::from("a")));
+		let chain = registry.chain(&TextStyle { font_family: Some(String::from("b")), ..Default::default() });
+		// unknown fallbacks are skipped and the default family is not repeated
+		assert_eq!(chain.len(), 2);
+		assert!(Arc::ptr_eq(&chain[1], &registry.select(Some("a"), 400, false).unwrap().font));
+	}
+
+	fn key(text: &str) -> LayoutKey {
+		LayoutKey {
+			text: text.to_string(),
+			spans: vec!(),
+			base: TextStyle::default(),
+			text_width: None,
+			text_height: None,
+			generation: 0,
+		}
+	}
+
+	#[test]
+	fn layout_cache_evicts_least_recently_used() {
+		let mut cache = LayoutCache::new(2);
+		assert!(cache.get(&key("a")).is_none());
+		cache.insert(key("a"), TextLayout::default());
+		cache.insert(key("b"), TextLayout::default());
+		assert!(cache.get(&key("a")).is_some());
+		cache.insert(key("c"), TextLayout::default());
+		// b is the least recently used one
+		assert!(cache.get(&key("b")).is_none());
+		assert!(cache.get(&key("a")).is_some() && cache.get(&key("c")).is_some());
+		// outdated fonts never hit
+		assert!(cache.get(&LayoutKey { generation: 1, ..key("a") }).is_none());
+		let stats = cache.stats();
+		assert_eq!((stats.hits, stats.misses, stats.evictions, stats.len), (3, 3, 1, 2));
+		assert_eq!(stats.hit_rate(), 0.5);
+		cache.set_capacity(1);
+		assert_eq!(cache.len(), 1);
+		let mut nothing = LayoutCache::new(0);
+		nothing.insert(key("a"), TextLayout::default());
+		assert!(nothing.is_empty());
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn layouts_are_cached_globally() {
+		use crate::shape::shape_elements::Text;
+		let text = Text { text: String::from("cached layout test"), ..Default::default() };
+		text.layout().unwrap();
+		let hits = LayoutCache::global().stats().hits;
+		assert_eq!(text.layout().unwrap(), text.layout().unwrap());
+		assert!(LayoutCache::global().stats().hits >= hits + 2);
+	}
+
+	#[test]
+	fn layout_key_with_nan_equals_itself() {
+		let key = LayoutKey {
+			text: "nan".to_string(),
+			spans: vec!((0..3, TextStyle { font_size: Some(f32::NAN), ..Default::default() })),
+			base: TextStyle::default(),
+			text_width: Some(f32::NAN),
+			text_height: None,
+			generation: 0,
+		};
+		assert_eq!(key, key.clone());
+	}
+}
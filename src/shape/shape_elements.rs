@@ -3,12 +3,17 @@
 //! You can use your shape by adding the [`Shape`] trait.
 //! Or just using [`Svg`] to print your svg with out animation.4
 
+use crate::shape::registry::FontRegistry;
+use crate::shape::registry::FontError;
+use crate::shape::registry::append_run;
+use crate::shape::registry::LayoutCache;
+use crate::shape::registry::LayoutKey;
 use std::sync::Arc;
-use once_cell::sync::Lazy;
 use fontdue::Font;
 use serde::*;
 use std::ops::Mul;
 use crate::prelude::ShapeMask;
+use crate::prelude::ClipMask;
 use std::ops::IndexMut;
 use std::ops::Index;
 use crate::shape::Area;
@@ -26,6 +31,7 @@ cfg_if::cfg_if! {
 		use lyon::path::*;
 		use lyon::geom::*;
 		use lyon::tessellation::*;
+		use crate::shape::atlas::GlyphAtlas;
 
 		impl Vec2 {
 			pub(crate) fn to_point(self) -> Point {
@@ -36,41 +42,338 @@ cfg_if::cfg_if! {
 		pub(crate) fn convert_path(input: Path, style: &Style, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
 			let clip = style.clip.clone();
 
-			let transform = Transform::identity()
-				.then_translate(Vector::new(style.position.x, style.position.y))
-				.then_translate(Vector::new(-style.transform_origin.x, -style.transform_origin.y))
-				.then_rotate(Angle { radians: style.rotate / 180.0 * PI })
-				.then_scale(style.size.x, style.size.y)
-				.then_translate(Vector::new(style.transform_origin.x, style.transform_origin.y));
+			let transform = style_transform(style);
 			let input = input.transformed(&transform);
 
 			// let path = pb.build();
 			let path = input;
+			let inverse = transform.inverse();
+			let fill = style.get_fill_paint();
+			let stroke = style.get_stroke_paint();
 			let mut geometry: VertexBuffers<Vertex, u32> = VertexBuffers::new();
 			let mut tessellator = FillTessellator::new();
 			{
 				let _ = tessellator.tessellate_path(&path, &FillOptions::tolerance(0.05), &mut BuffersBuilder::new(&mut geometry, |vertex: FillVertex| {
-					Vertex {
-						position: [vertex.position().x, vertex.position().y, 0.0],
-						color: style.fill.normalized()
-					}
+					paint_vertex(vertex.position(), &fill, &inverse)
 				}));
 			}
-			if style.stroke_width > 0.0 && style.stroke_color[3] != 0 {
+			if style.stroke_width > 0.0 && stroke.is_visible() {
 				let mut tessellator = StrokeTessellator::new();
 				{
-					let _ = tessellator.tessellate_path(&path, &StrokeOptions::tolerance(0.05).with_line_width(style.stroke_width), &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
-						Vertex {
-							position: [vertex.position().x, vertex.position().y, 0.0],
-							color: style.stroke_color.normalized()
-						}
+					let options = StrokeOptions::tolerance(0.05)
+						.with_line_width(style.stroke_width)
+						.with_line_cap(style.line_cap.into())
+						.with_line_join(style.line_join.into())
+						.with_miter_limit(style.miter_limit.max(StrokeOptions::MINIMUM_MITER_LIMIT));
+					let _ = tessellator.tessellate_path(&path, &options, &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
+						paint_vertex(vertex.position(), &stroke, &inverse)
 					}));
 				}
 			}
 			(geometry.vertices.into_par_iter().map(|inner| Vertex {
 				position: [inner.position[0] / size.x * 2.0 - 1.0, - (inner.position[1] / size.y * 2.0 - 1.0), 0.0],
 				..inner
-			}).collect(), geometry.indices, Area::new((clip.area[0] / size * 2.0) - Vec2::same(1.0), (clip.area[1] / size * 2.0) - Vec2::same(1.0)))
+			}).collect(), geometry.indices, normalize_clip(clip, size))
+		}
+
+		/// normalize clip in the same way as vertexs
+		pub(crate) fn normalize_clip(clip: Area, size: Vec2) -> Area {
+			Area::new((clip.area[0] / size * 2.0) - Vec2::same(1.0), (clip.area[1] / size * 2.0) - Vec2::same(1.0))
+		}
+
+		/// the transform used when converting a shape into vertexs, same as [`Style::transform_point`].
+		pub(crate) fn style_transform(style: &Style) -> Transform<f32> {
+			Transform::identity()
+				.then_translate(Vector::new(-style.transform_origin.x, -style.transform_origin.y))
+				.then_rotate(Angle { radians: style.rotate })
+				.then_scale(style.size.x, style.size.y)
+				.then_translate(Vector::new(style.transform_origin.x + style.position.x, style.transform_origin.y + style.position.y))
+		}
+
+		/// apply transform of given style to normalized vertexs, useful for nested shapes.
+		pub(crate) fn transform_vertexs(vertexs: &mut [Vertex], style: &Style, size: Vec2) {
+			let transform = style_transform(style);
+			vertexs.par_iter_mut().for_each(|vertex| {
+				let position = point((vertex.position[0] + 1.0) / 2.0 * size.x, (1.0 - vertex.position[1]) / 2.0 * size.y);
+				let position = transform.transform_point(position);
+				vertex.position = [position.x / size.x * 2.0 - 1.0, - (position.y / size.y * 2.0 - 1.0), vertex.position[2]];
+			});
+		}
+
+		/// convert outlines into a path, used by text masks.
+		pub(crate) fn outlines_path(outlines: &[Outline]) -> Path {
+			let mut pb = Path::builder();
+			let mut is_open = false;
+			for command in outlines.iter().flat_map(|t| t.commands.iter()) {
+				match command {
+					PathCommand::MoveTo(t) => {
+						if is_open {
+							pb.end(true);
+						}
+						pb.begin(t.to_point());
+						is_open = true;
+					},
+					PathCommand::LineTo(t) => {
+						pb.line_to(t.to_point());
+					},
+					PathCommand::QuadTo(t1, t2) => {
+						pb.quadratic_bezier_to(t1.to_point(), t2.to_point());
+					},
+					PathCommand::CubicTo(t1, t2, t3) => {
+						pb.cubic_bezier_to(t1.to_point(), t2.to_point(), t3.to_point());
+					},
+					PathCommand::Close => {
+						if is_open {
+							pb.end(true);
+							is_open = false;
+						}
+					},
+				}
+			}
+			if is_open {
+				pb.end(true);
+			}
+			pb.build()
+		}
+
+		/// convert laid out glyphs into quads sampling [`GlyphAtlas::global`], glyphs are cut by `clip` and then moved by `place`.
+		///
+		/// `color` gives color of every glyph, glyphs without one are colored by [`Style::get_fill_paint`] at their corners.
+		/// `place` moves a point of `index`th glyph to where it should be, before [`Style`] transform.
+		pub(crate) fn glyph_vertexs(glyphs: &[LayoutGlyph], clip: Option<Area>, color: impl Fn(&LayoutGlyph) -> Option<[f32; 4]>, place: impl Fn(usize, Vec2) -> Vec2, style: &Style, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
+			let transform = style_transform(style);
+			let inverse = transform.inverse();
+			let fill = style.get_fill_paint();
+			let normalize = |point: Point| Vec2::new(point.x / size.x * 2.0 - 1.0, - (point.y / size.y * 2.0 - 1.0));
+			// find fonts first, atlas should not be held while reading fonts
+			let mut fonts: Vec<Arc<Font>> = vec!();
+			{
+				let registry = FontRegistry::global();
+				for glyph in glyphs {
+					if !fonts.iter().any(|t| t.file_hash() == glyph.font_hash) {
+						if let Some(font) = registry.font_by_hash(glyph.font_hash) {
+							fonts.push(font);
+						}
+					}
+				}
+			}
+			let mut vertexs = vec!();
+			let mut indices = vec!();
+			// rasterize in the size glyphs are drawn, so scaled text stays crisp
+			let raster_scale = style.size.x.abs().max(style.size.y.abs());
+			if raster_scale == 0.0 {
+				return (vertexs, indices, normalize_clip(style.clip, size))
+			}
+			let mut atlas = GlyphAtlas::global();
+			for (index, glyph) in glyphs.iter().enumerate() {
+				if glyph.character.is_whitespace() || glyph.character.is_control() {
+					continue;
+				}
+				let font = match fonts.iter().find(|t| t.file_hash() == glyph.font_hash) {
+					Some(t) => t,
+					None => continue,
+				};
+				let atlas_glyph = match atlas.glyph(font, glyph.glyph_index, glyph.font_size * raster_scale) {
+					Some(t) => t,
+					None => continue,
+				};
+				let area = atlas_glyph.area(glyph.origin, glyph.font_size);
+				// cut glyphs outside clip and their uv together
+				let visible = match clip {
+					Some(clip) => area.cross_part(&clip),
+					None => area,
+				};
+				if visible.is_empty() {
+					continue;
+				}
+				let to_uv = |local: Vec2| {
+					let t = (local - area.area[0]) / area.width_and_height();
+					atlas_glyph.uv.area[0] + t * atlas_glyph.uv.width_and_height()
+				};
+				let color = color(glyph);
+				let offset = vertexs.len() as u32;
+				for local in [visible.left_top(), visible.right_top(), visible.right_bottom(), visible.left_bottom()] {
+					let position = transform.transform_point(place(index, local).to_point());
+					let color = color.unwrap_or_else(|| paint_vertex(position, &fill, &inverse).color);
+					let position = normalize(position);
+					let uv = to_uv(local);
+					vertexs.push(Vertex {
+						position: [position.x, position.y, 0.0],
+						color,
+						uv: [uv.x, uv.y],
+					});
+				}
+				indices.extend([offset, offset + 1, offset + 2, offset, offset + 2, offset + 3]);
+			}
+			(vertexs, indices, normalize_clip(style.clip, size))
+		}
+
+		/// append quads of [`TextLayout::decorations`] to vertexs made by [`glyph_vertexs`], they sample [`GlyphAtlas::white_uv`].
+		///
+		/// `color` works as in [`glyph_vertexs`], `place` turns the visible part of a decoration into quads where it is drawn, see [`PathGlyph::place`].
+		pub(crate) fn decoration_vertexs(layout: &TextLayout, color: impl Fn(&LayoutDecoration) -> Option<[f32; 4]>, place: impl Fn(Area) -> Vec<Vec<Vec2>>, style: &Style, size: Vec2, vertexs: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+			if layout.decorations.is_empty() {
+				return;
+			}
+			let transform = style_transform(style);
+			let inverse = transform.inverse();
+			let fill = style.get_fill_paint();
+			let white = GlyphAtlas::global().white_uv();
+			for decoration in &layout.decorations {
+				let visible = match layout.clip {
+					Some(clip) => decoration.area.cross_part(&clip),
+					None => decoration.area,
+				};
+				if visible.is_empty() {
+					continue;
+				}
+				let color = color(decoration);
+				for quad in place(visible) {
+					let offset = vertexs.len() as u32;
+					for local in quad {
+						let position = transform.transform_point(local.to_point());
+						vertexs.push(Vertex {
+							position: [position.x / size.x * 2.0 - 1.0, - (position.y / size.y * 2.0 - 1.0), 0.0],
+							color: color.unwrap_or_else(|| paint_vertex(position, &fill, &inverse).color),
+							uv: [white.x, white.y],
+						});
+					}
+					indices.extend([offset, offset + 1, offset + 2, offset, offset + 2, offset + 3]);
+				}
+			}
+		}
+
+		/// append stroked `outlines` to vertexs made by [`glyph_vertexs`] when [`Style::stroke_width`] is given, they sample [`GlyphAtlas::white_uv`].
+		pub(crate) fn stroke_text_vertexs(outlines: &[Outline], clip: Option<Area>, style: &Style, size: Vec2, vertexs: &mut Vec<Vertex>, indices: &mut Vec<u32>) {
+			let stroke = style.get_stroke_paint();
+			if style.stroke_width <= 0.0 || !stroke.is_visible() {
+				return;
+			}
+			let transform = style_transform(style);
+			let inverse = transform.inverse();
+			let path = outlines_path(outlines).transformed(&transform);
+			let white = GlyphAtlas::global().white_uv();
+			let mut geometry: VertexBuffers<Vertex, u32> = VertexBuffers::new();
+			let options = StrokeOptions::tolerance(0.05)
+				.with_line_width(style.stroke_width)
+				.with_line_cap(style.line_cap.into())
+				.with_line_join(style.line_join.into())
+				.with_miter_limit(style.miter_limit.max(StrokeOptions::MINIMUM_MITER_LIMIT));
+			let _ = StrokeTessellator::new().tessellate_path(&path, &options, &mut BuffersBuilder::new(&mut geometry, |vertex: StrokeVertex| {
+				let position = vertex.position();
+				Vertex {
+					position: [position.x / size.x * 2.0 - 1.0, - (position.y / size.y * 2.0 - 1.0), 0.0],
+					uv: [white.x, white.y],
+					..paint_vertex(position, &stroke, &inverse)
+				}
+			}));
+			let (mut stroke_vertexs, mut stroke_indices) = (geometry.vertices, geometry.indices);
+			// glyphs are cut by the clip of layout, so are their strokes
+			if let Some(clip) = clip {
+				let polygon: Vec<Vec2> = [clip.left_top(), clip.right_top(), clip.right_bottom(), clip.left_bottom()].into_iter().map(|t| {
+					let t = transform.transform_point(t.to_point());
+					Vec2::new(t.x / size.x * 2.0 - 1.0, - (t.y / size.y * 2.0 - 1.0))
+				}).collect();
+				(stroke_vertexs, stroke_indices) = clip_vertexs(&stroke_vertexs, &stroke_indices, &polygon);
+			}
+			let offset = vertexs.len() as u32;
+			vertexs.extend(stroke_vertexs);
+			indices.extend(stroke_indices.into_iter().map(|t| t + offset));
+		}
+
+		/// clip triangles with a convex polygon on cpu, polygon should be normalized in the same way as vertexs.
+		pub(crate) fn clip_vertexs(vertexs: &[Vertex], indices: &[u32], polygon: &[Vec2]) -> (Vec<Vertex>, Vec<u32>) {
+			fn lerp(from: &Vertex, to: &Vertex, t: f32) -> Vertex {
+				let mut back = *from;
+				for i in 0..3 {
+					back.position[i] = from.position[i] + (to.position[i] - from.position[i]) * t;
+				}
+				for i in 0..4 {
+					back.color[i] = from.color[i] + (to.color[i] - from.color[i]) * t;
+				}
+				for i in 0..2 {
+					back.uv[i] = from.uv[i] + (to.uv[i] - from.uv[i]) * t;
+				}
+				back
+			}
+
+			let len = polygon.len();
+			if len < 3 {
+				return (vec!(), vec!())
+			}
+			let mut area = 0.0;
+			for i in 0..len {
+				area += polygon[i].cross(polygon[(i + 1) % len]);
+			}
+			let sign = if area < 0.0 { -1.0 } else { 1.0 };
+			let mut back_vertexs = vec!();
+			let mut back_indices = vec!();
+			for triangle in indices.chunks_exact(3) {
+				let mut current: Vec<Vertex> = triangle.iter().map(|i| vertexs[*i as usize]).collect();
+				for i in 0..len {
+					let (a, b) = (polygon[i], polygon[(i + 1) % len]);
+					let distance = |vertex: &Vertex| sign * (b - a).cross(Vec2::new(vertex.position[0], vertex.position[1]) - a);
+					let mut next = vec!();
+					for j in 0..current.len() {
+						let now = &current[j];
+						let last = &current[(j + current.len() - 1) % current.len()];
+						let (now_distance, last_distance) = (distance(now), distance(last));
+						if (now_distance >= 0.0) != (last_distance >= 0.0) {
+							next.push(lerp(last, now, last_distance / (last_distance - now_distance)));
+						}
+						if now_distance >= 0.0 {
+							next.push(*now);
+						}
+					}
+					current = next;
+					if current.len() < 3 {
+						break;
+					}
+				}
+				if current.len() < 3 {
+					continue;
+				}
+				let offset = back_vertexs.len() as u32;
+				for i in 1..(current.len() as u32 - 1) {
+					back_indices.extend([offset, offset + i, offset + i + 1]);
+				}
+				back_vertexs.append(&mut current);
+			}
+			(back_vertexs, back_indices)
+		}
+
+		/// evaluate paint at the untransformed position of a vertex
+		fn paint_vertex(position: Point, paint: &Paint, inverse: &Option<Transform<f32>>) -> Vertex {
+			let local = match inverse {
+				Some(t) => t.transform_point(position),
+				None => position,
+			};
+			let local = Vec2::new(local.x, local.y);
+			Vertex {
+				position: [position.x, position.y, 0.0],
+				color: paint.color_at(local).normalized(),
+				uv: paint.uv(local),
+			}
+		}
+
+		impl From<LineCap> for lyon::tessellation::LineCap {
+			fn from(input: LineCap) -> Self {
+				match input {
+					LineCap::Butt => Self::Butt,
+					LineCap::Round => Self::Round,
+					LineCap::Square => Self::Square,
+				}
+			}
+		}
+
+		impl From<LineJoin> for lyon::tessellation::LineJoin {
+			fn from(input: LineJoin) -> Self {
+				match input {
+					LineJoin::Miter => Self::Miter,
+					LineJoin::Round => Self::Round,
+					LineJoin::Bevel => Self::Bevel,
+				}
+			}
 		}
 	}
 }
@@ -79,9 +382,9 @@ cfg_if::cfg_if! {
 pub const EM: f32 = 16.0;
 /// for text usage
 pub const CORRECTION: f32 = 1.0;
-/// the default font
+/// the default font, requires feature `default-font`.
+#[cfg(feature = "default-font")]
 pub const DEFAULT_FONT: &[u8; 5470824] = include_bytes!("../../font.ttf");
-static FONT: Lazy<Arc<Font>> = Lazy::new(|| {Arc::new(fontdue::Font::from_bytes(DEFAULT_FONT as &[u8], Default::default()).expect("loading font failed"))});
 
 /// a trait for a shape
 pub trait Shape: Default + Clone + Debug + PartialEq + Animate {
@@ -112,6 +415,65 @@ pub trait Animate {
 pub struct Vertex {
 	pub position: [f32; 3],
 	pub color: [f32; 4],
+	/// for gradient paints, this is the position in gradient space, see more in [`Paint::uv`].
+	/// for images and texts, this is texture coordinate, (0.0, 0.0) for left top and (1.0, 1.0) for right bottom. texts sample [`crate::shape::atlas::GlyphAtlas`].
+	pub uv: [f32; 2],
+}
+
+/// a batch of vertexs sharing the same render state, see more in [`crate::shape::Painter::draw_list`].
+#[cfg(feature = "vertexs")]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct DrawCommand {
+	pub vertexs: Vec<Vertex>,
+	pub indices: Vec<u32>,
+	/// where you should cut, normalized.
+	pub clip: Area,
+	pub layer: Layer,
+	/// a renderer should switch pipline when this changes.
+	pub blend_mode: BlendMode,
+	/// not multiplied into vertexs, should be applied to the whole batch.
+	pub opacity: f32,
+	/// clip masks can not be clipped on cpu, only pixels covered by every mask should be drawn, normalized.
+	pub stencil_masks: Vec<(Vec<Vertex>, Vec<u32>)>,
+	/// the image id vertexs should be textured with, see more in [`Image`]. use `uv` in vertexs to sample.
+	pub texture: Option<String>,
+	/// effects should be drawn with this batch, commands with effects will never be merged.
+	pub effects: Vec<EffectRecord>,
+}
+
+/// a renderer friendly record of [`Effect`].
+#[cfg(feature = "vertexs")]
+#[derive(Clone, Debug, PartialEq)]
+pub struct EffectRecord {
+	pub effect: Effect,
+	/// for shadows, geometry should be drawn (and blured) under the shape, normalized. empty for [`Effect::GaussianBlur`].
+	///
+	/// when `rounded_rect` is not [`Option::None`], this is a quad covering the whole shadow with uv set to pixel position.
+	/// otherwise this is the silhouette of the shape filled with shadow color.
+	pub vertexs: Vec<Vertex>,
+	pub indices: Vec<u32>,
+	/// the rect and its rounding in pixel which casts this shadow, only available for unrotated [`Rect`] and [`Circle`].
+	/// can be used to caculate shadow analytically.
+	pub rounded_rect: Option<(Area, Vec2)>,
+	/// blur standard deviation in pixel
+	pub sigma: f32,
+	/// how large this effect may take in pixel
+	pub area: Area,
+}
+
+#[cfg(feature = "vertexs")]
+impl DrawCommand {
+	/// check if two commands can be drawn in a single batch
+	pub fn is_same_state(&self, other: &DrawCommand) -> bool {
+		self.clip == other.clip && self.layer == other.layer && self.blend_mode == other.blend_mode && self.opacity == other.opacity && self.effects.is_empty() && other.effects.is_empty() && self.stencil_masks == other.stencil_masks && self.texture == other.texture
+	}
+
+	/// append other command into current one, will not check states.
+	pub fn append(&mut self, other: DrawCommand) {
+		let offset = self.vertexs.len() as u32;
+		self.vertexs.extend(other.vertexs);
+		self.indices.extend(other.indices.into_iter().map(|t| t + offset));
+	}
 }
 
 impl From<(Vec2, [u8;4])> for Vertex {
@@ -120,6 +482,7 @@ impl From<(Vec2, [u8;4])> for Vertex {
 		Self {
 			position: [position.x, position.y, 0.0],
 			color: [color[0] as f32 / 255.0,color[1] as f32 / 255.0,color[2] as f32 / 255.0,color[3] as f32 / 255.0],
+			uv: [0.0; 2],
 		}
 	}
 }
@@ -130,6 +493,7 @@ impl From<(Vec2, Color)> for Vertex {
 		Self {
 			position: [position.x, position.y, 0.0],
 			color: color.normalized(),
+			uv: [0.0; 2],
 		}
 	}
 }
@@ -137,12 +501,20 @@ impl From<(Vec2, Color)> for Vertex {
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
 #[serde(default)]
 /// for general styles of a shape
+///
+/// ids used by [`Style::animate`]:
+/// - 0..[`Style::FIXED_LEN`] are fields with fixed ids, see docs of every field.
+/// - [`Style::FIXED_LEN`].. are ids of `fill_paint` and then `stroke_paint`, they move when paints are replaced so they always come last.
 pub struct Style {
 	/// Where we are, id = 0 or 1 represents position.x or position.y.
 	pub position: Vec2,
-	/// rotate and scale center, id = 2 or 3 represents transform_origin.x or transform_origin.y.
+	/// rotate and scale center relative to `position`, id = 2 or 3 represents transform_origin.x or transform_origin.y.
+	///
+	/// **breaking:** this used to be an absolute point, subtract `position` from it if you have stored one.
 	pub transform_origin: Vec2,
-	/// follows angle measure, id = 4 repensents this attribute.
+	/// follows radian measure, id = 4 repensents this attribute.
+	///
+	/// **breaking:** this used to be in degrees, serialized styles and animations from older versions should be converted with [`f32::to_radians`].
 	pub rotate: f32,
 	/// normally is [`crate::math::Vec2::NOT_TO_SCALE`]. Will **not** affect stroke width. id = 5 or 6 represents size.x or size.y.
 	pub size: Vec2,
@@ -152,11 +524,202 @@ pub struct Style {
 	pub stroke_width: f32,
 	/// stands for rgba, id = 12, 13, 14 or 15 represents ```fill[0]``` ```fill[1]``` ```fill[2]``` or ```fill[3]```.
 	pub stroke_color: Color,
+	/// will replace `fill` if is not [`Option::None`]. ids start from [`Style::FIXED_LEN`], see more in [`Paint`]'s [`Animate`] implementation.
+	pub fill_paint: Option<Paint>,
+	/// will replace `stroke_color` if is not [`Option::None`]. ids start right after `fill_paint`'s ids.
+	pub stroke_paint: Option<Paint>,
 	#[serde(skip)]
 	/// where should we draw?
 	pub layer: Layer,
 	/// you only need to show shapes inside this clip
-	pub clip: Area
+	pub clip: Area,
+	/// how the end of an open stroke looks like
+	pub line_cap: LineCap,
+	/// how two stroke segments are connected
+	pub line_join: LineJoin,
+	/// only works with [`LineJoin::Miter`], a miter longer than `miter_limit * stroke_width` will be beveled. id = 16 repensents this attribute.
+	pub miter_limit: f32,
+	/// how this shape mix with what is already drawn
+	pub blend_mode: BlendMode,
+	/// opacity of the whole shape, applied after fill and stroke are composited. id = 17 repensents this attribute.
+	pub opacity: f32,
+	/// effects like shadow or blur, applied by order.
+	pub effects: Vec<Effect>,
+	/// only things inside every mask will show, works together with `clip`.
+	pub clip_masks: Vec<ClipMask>,
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+/// a visual effect of a shape
+pub enum Effect {
+	/// a shadow under the shape
+	DropShadow(DropShadow),
+	/// a blured halo around the shape
+	OuterGlow(OuterGlow),
+	/// blur the shape itself, the value is blur radius.
+	GaussianBlur(f32),
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Copy)]
+#[serde(default)]
+/// a shadow under the shape, refer to css `box-shadow`.
+pub struct DropShadow {
+	pub offset: Vec2,
+	/// blur radius, standard deviation is half of it.
+	pub blur: f32,
+	pub color: Color,
+}
+
+impl Default for DropShadow {
+	fn default() -> Self {
+		Self {
+			offset: Vec2::ZERO,
+			blur: 0.0,
+			color: [0, 0, 0, 128].into(),
+		}
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Copy)]
+#[serde(default)]
+/// a blured halo around the shape.
+pub struct OuterGlow {
+	/// blur radius, standard deviation is half of it.
+	pub radius: f32,
+	pub color: Color,
+}
+
+impl Default for OuterGlow {
+	fn default() -> Self {
+		Self {
+			radius: 0.0,
+			color: Color::WHITE,
+		}
+	}
+}
+
+impl Effect {
+	/// get the shadow this effect casts, [`Effect::OuterGlow`] is a shadow without offset. [`Option::None`] for [`Effect::GaussianBlur`].
+	pub fn shadow(&self) -> Option<DropShadow> {
+		match self {
+			Self::DropShadow(t) => Some(*t),
+			Self::OuterGlow(t) => Some(DropShadow {
+				offset: Vec2::ZERO,
+				blur: t.radius,
+				color: t.color,
+			}),
+			Self::GaussianBlur(_) => None,
+		}
+	}
+
+	/// how far this effect may paint outside the shape
+	pub fn margin(&self) -> Vec2 {
+		match self {
+			Self::GaussianBlur(t) => Vec2::same(t * 1.5),
+			_ => {
+				let shadow = self.shadow().unwrap_or_default();
+				// 3 times of standard deviation
+				Vec2::same(shadow.blur * 1.5) + Vec2::new(shadow.offset.x.abs(), shadow.offset.y.abs())
+			}
+		}
+	}
+
+	/// scale effect with (0,0), see more in [`crate::shape::Shape::pre_scale`]
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		match self {
+			Self::DropShadow(t) => {
+				t.offset = t.offset * scale_factor;
+				t.blur *= scale_factor;
+			},
+			Self::OuterGlow(t) => t.radius *= scale_factor,
+			Self::GaussianBlur(t) => *t *= scale_factor,
+		}
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+/// how a shape mix with what is already drawn, refer to css `mix-blend-mode`.
+pub enum BlendMode {
+	/// source over
+	#[default] Normal,
+	Multiply,
+	Screen,
+	/// add colors together, css calls it `plus-lighter`
+	Additive,
+	Overlay,
+	Darken,
+	Lighten,
+	ColorDodge,
+	ColorBurn,
+	HardLight,
+	SoftLight,
+	Difference,
+	Exclusion,
+}
+
+impl BlendMode {
+	/// get svg expression
+	pub fn svg(&self) -> &'static str {
+		match self {
+			Self::Normal => "normal",
+			Self::Multiply => "multiply",
+			Self::Screen => "screen",
+			Self::Additive => "plus-lighter",
+			Self::Overlay => "overlay",
+			Self::Darken => "darken",
+			Self::Lighten => "lighten",
+			Self::ColorDodge => "color-dodge",
+			Self::ColorBurn => "color-burn",
+			Self::HardLight => "hard-light",
+			Self::SoftLight => "soft-light",
+			Self::Difference => "difference",
+			Self::Exclusion => "exclusion",
+		}
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+/// how the end of an open stroke looks like, refer to svg `stroke-linecap`.
+pub enum LineCap {
+	/// stops right at the end point
+	#[default] Butt,
+	/// a half circle with stroke width as its diameter
+	Round,
+	/// a half square with stroke width as its side length
+	Square,
+}
+
+impl LineCap {
+	/// get svg expression
+	pub fn svg(&self) -> &'static str {
+		match self {
+			Self::Butt => "butt",
+			Self::Round => "round",
+			Self::Square => "square",
+		}
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+/// how two stroke segments are connected, refer to svg `stroke-linejoin`.
+pub enum LineJoin {
+	/// sharp conner, see [`Style::miter_limit`]
+	#[default] Miter,
+	/// round conner
+	Round,
+	/// cut conner
+	Bevel,
+}
+
+impl LineJoin {
+	/// get svg expression
+	pub fn svg(&self) -> &'static str {
+		match self {
+			Self::Miter => "miter",
+			Self::Round => "round",
+			Self::Bevel => "bevel",
+		}
+	}
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, PartialOrd, Ord, Hash)]
@@ -394,129 +957,737 @@ impl Color {
 	}
 }
 
-impl Default for Style {
-	fn default() -> Self {
-		Self {
-			position: Vec2::default(),
-			transform_origin: Vec2::default(),
-			rotate: 0.0,
-			size: Vec2::NOT_TO_SCALE,
-			fill: [255,255,255,255].into(),
-			stroke_width: 0.0,
-			stroke_color: [0; 4].into(),
-			layer: Layer::Bottom,
-			clip: Area::ZERO
+impl Color {
+	/// linear interpolate between two colors, t should in [0..=1].
+	pub fn lerp(self, other: Color, t: f32) -> Self {
+		let t = compress(t);
+		let mut back = self;
+		for i in 0..4 {
+			back[i] = (self[i] as f32 + (other[i] as f32 - self[i] as f32) * t).round() as u8;
 		}
+		back
 	}
-}
 
-impl Style {
-	/// such as color stroke etc.
-	pub fn svg_basic_settings(&self) -> String {
-		format!("stroke-width=\"{}\" stroke=\"rgb({},{},{})\" stroke-opacity=\"{}\" fill=\"rgb({},{},{})\"  fill-opacity=\"{}\" transform-origin=\"{},{}\" transform=\"rotate({})\" transform=\"scale({}, {})\"",
-			self.stroke_width,
-			self.stroke_color[0],
-			self.stroke_color[1],
-			self.stroke_color[2],
-			self.stroke_color[3] as f32 / 255.0,
-			self.fill[0],
-			self.fill[1],
-			self.fill[2],
-			self.fill[3] as f32 / 255.0,
-			self.transform_origin.x,
-			self.transform_origin.y,
-			self.rotate / std::f32::consts::PI * 360.0,
-			self.size.x,
-			self.size.y,
-			)
+	/// get svg expression without alpha
+	pub fn svg(&self) -> String {
+		format!("rgb({},{},{})", self[0], self[1], self[2])
 	}
+}
 
-	/// same as [`Animate`]
-	pub fn animate(&mut self, id: usize, change_to: f32) {
-		fn compress(input: f32) -> f32 {
-			if input > 1.0 {
-				1.0
-			}else if input < 0.0 {
-				0.0
-			}else {
-				input
-			}
-		}
+/// what we fill or stroke a shape with
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub enum Paint {
+	Solid(Color),
+	Linear(LinearGradient),
+	Radial(RadialGradient),
+	/// svg do not have conic gradient, so the average color of stops will be used in svg.
+	Conic(ConicGradient),
+}
 
-		match id {
-			0 => self.position.x = change_to,
-			1 => self.position.y = change_to,
-			2 => self.transform_origin.x = change_to,
-			3 => self.transform_origin.y = change_to,
-			4 => self.rotate = change_to,
-			5 => self.size.x = change_to,
-			6 => self.size.y = change_to,
-			7 => self.fill[0] = (compress(change_to) * 255.0) as u8,
-			8 => self.fill[1] = (compress(change_to) * 255.0) as u8,
-			9 => self.fill[2] = (compress(change_to) * 255.0) as u8,
-			10 => self.fill[3] = (compress(change_to) * 255.0) as u8,
-			11 => self.stroke_width = change_to,
-			12 => self.stroke_color[0] = (compress(change_to) * 255.0) as u8,
-			13 => self.stroke_color[1] = (compress(change_to) * 255.0) as u8,
-			14 => self.stroke_color[2] = (compress(change_to) * 255.0) as u8,
-			15 => self.stroke_color[3] = (compress(change_to) * 255.0) as u8,
-			_ => {}
-		}
+impl Default for Paint {
+	fn default() -> Self {
+		Self::Solid(Color::WHITE)
 	}
+}
 
-	pub fn len(&self) -> usize { 16 }
+impl From<Color> for Paint {
+	fn from(color: Color) -> Self {
+		Self::Solid(color)
+	}
 }
 
-impl Add for Style {
-	type Output = Self;
-	fn add(self, rhs: Self) -> Self::Output {
+/// a color at a given place of gradient
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Copy)]
+#[serde(default)]
+pub struct GradientStop {
+	/// should in [0..=1]
+	pub offset: f32,
+	pub color: Color,
+}
+
+impl Default for GradientStop {
+	fn default() -> Self {
 		Self {
-			position: self.position + rhs.position,
-			transform_origin: self.transform_origin + rhs.position,
-			rotate: self.rotate + rhs.rotate,
-			size: self.size + rhs.size,
-			fill: rhs.fill,
-			stroke_width: self.stroke_width + rhs.stroke_width,
-			stroke_color: rhs.stroke_color,
-			..self
+			offset: 0.0,
+			color: Color::WHITE,
 		}
 	}
 }
 
-impl Sub for Style {
-	type Output = Self;
-	fn sub(self, rhs: Self) -> Self::Output {
-		Self {
-			position: self.position - rhs.position,
-			transform_origin: self.transform_origin - rhs.position,
-			rotate: self.rotate - rhs.rotate,
-			size: self.size - rhs.size,
-			stroke_width: self.stroke_width - rhs.stroke_width,
-			..self
+/// what to do outside [0..=1] of a gradient, refer to svg `spreadMethod`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+pub enum SpreadMode {
+	/// use the color at the end.
+	#[default] Pad,
+	/// go back and forth.
+	Reflect,
+	/// start again.
+	Repeat,
+}
+
+impl SpreadMode {
+	/// get svg expression
+	pub fn svg(&self) -> &'static str {
+		match self {
+			Self::Pad => "pad",
+			Self::Reflect => "reflect",
+			Self::Repeat => "repeat",
+		}
+	}
+
+	/// map any value into [0..=1]
+	pub fn apply(&self, t: f32) -> f32 {
+		match self {
+			Self::Pad => compress(t),
+			Self::Repeat => t - t.floor(),
+			Self::Reflect => {
+				let t = t.abs() % 2.0;
+				if t > 1.0 {
+					2.0 - t
+				}else {
+					t
+				}
+			},
 		}
 	}
 }
 
+/// a gradient goes from `start` to `end`, positions are relative to [`Style::position`].
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
 #[serde(default)]
-/// a circle, uh actually a ellipse if you have applied size.
-pub struct Circle {
+pub struct LinearGradient {
+	pub start: Vec2,
+	pub end: Vec2,
+	/// should be sorted by offset
+	pub stops: Vec<GradientStop>,
+	pub spread: SpreadMode,
+}
+
+/// a gradient goes from `center` to a circle with `radius`, positions are relative to [`Style::position`].
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct RadialGradient {
+	pub center: Vec2,
 	pub radius: f32,
+	/// should be sorted by offset
+	pub stops: Vec<GradientStop>,
+	pub spread: SpreadMode,
 }
 
-impl Shape for Circle {
-	fn into_svg(&self, style: &Style) -> String {
-		format!("<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" {}/>", 
-			style.position.x, 
-			style.position.y,
-			self.radius * style.size.x,
-			self.radius * style.size.y,
-			style.svg_basic_settings(),
-			)
+/// a gradient goes around `center` clockwise starts from `angle`, positions are relative to [`Style::position`].
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct ConicGradient {
+	pub center: Vec2,
+	/// follows radian measure
+	pub angle: f32,
+	/// should be sorted by offset
+	pub stops: Vec<GradientStop>,
+	pub spread: SpreadMode,
+}
+
+impl Paint {
+	fn stops(&self) -> &[GradientStop] {
+		match self {
+			Self::Solid(_) => &[],
+			Self::Linear(t) => &t.stops,
+			Self::Radial(t) => &t.stops,
+			Self::Conic(t) => &t.stops,
+		}
 	}
 
-	fn get_area(&self, style: &Style) -> Area {
-		Rect {
+	fn stops_mut(&mut self) -> &mut [GradientStop] {
+		match self {
+			Self::Solid(_) => &mut [],
+			Self::Linear(t) => &mut t.stops,
+			Self::Radial(t) => &mut t.stops,
+			Self::Conic(t) => &mut t.stops,
+		}
+	}
+
+	/// how much ids are used before stops
+	fn geometry_len(&self) -> usize {
+		match self {
+			Self::Solid(_) => 4,
+			Self::Linear(_) => 4,
+			Self::Radial(_) => 3,
+			Self::Conic(_) => 3,
+		}
+	}
+
+	/// check if anything can be seen using this paint
+	pub fn is_visible(&self) -> bool {
+		match self {
+			Self::Solid(t) => t[3] != 0,
+			_ => self.stops().iter().any(|stop| stop.color[3] != 0),
+		}
+	}
+
+	/// get the gradient value of a point without spread, point is relative to [`Style::position`]. always 0.0 for [`Paint::Solid`].
+	pub fn offset_at(&self, point: Vec2) -> f32 {
+		match self {
+			Self::Solid(_) => 0.0,
+			Self::Linear(t) => {
+				let direction = t.end - t.start;
+				let len = direction.dot(direction);
+				if len == 0.0 {
+					0.0
+				}else {
+					(point - t.start).dot(direction) / len
+				}
+			},
+			Self::Radial(t) => {
+				if t.radius == 0.0 {
+					0.0
+				}else {
+					(point - t.center).len() / t.radius
+				}
+			},
+			Self::Conic(t) => {
+				let delta = (point - t.center).rotate(-t.angle);
+				if delta == Vec2::ZERO {
+					0.0
+				}else {
+					delta.angle() / (2.0 * PI)
+				}
+			},
+		}
+	}
+
+	/// get the color of a point, point is relative to [`Style::position`].
+	pub fn color_at(&self, point: Vec2) -> Color {
+		match self {
+			Self::Solid(t) => *t,
+			Self::Linear(t) => sample_stops(&t.stops, t.spread.apply(self.offset_at(point))),
+			Self::Radial(t) => sample_stops(&t.stops, t.spread.apply(self.offset_at(point))),
+			Self::Conic(t) => sample_stops(&t.stops, t.spread.apply(self.offset_at(point))),
+		}
+	}
+
+	/// get the position in gradient space of a point, point is relative to [`Style::position`].
+	///
+	/// for [`Paint::Linear`] it is `[offset, 0.0]`, for [`Paint::Radial`] it is the delta to center divided by radius,
+	/// for [`Paint::Conic`] it is the delta to center rotated by `-angle`, for [`Paint::Solid`] it is always `[0.0, 0.0]`.
+	/// use this with [`Self::ramp`] if you want to caculate gradient per pixel.
+	pub fn uv(&self, point: Vec2) -> [f32; 2] {
+		match self {
+			Self::Solid(_) => [0.0; 2],
+			Self::Linear(_) => [self.offset_at(point), 0.0],
+			Self::Radial(t) => {
+				let delta = if t.radius == 0.0 {
+					Vec2::ZERO
+				}else {
+					(point - t.center) / t.radius
+				};
+				[delta.x, delta.y]
+			},
+			Self::Conic(t) => {
+				let delta = (point - t.center).rotate(-t.angle);
+				[delta.x, delta.y]
+			},
+		}
+	}
+
+	/// sample this paint into a lookup table with `resolution` colors, spread is not applied.
+	pub fn ramp(&self, resolution: usize) -> Vec<Color> {
+		match self {
+			Self::Solid(t) => vec!(*t; resolution),
+			_ => (0..resolution).map(|i| {
+				let t = if resolution > 1 {
+					i as f32 / (resolution - 1) as f32
+				}else {
+					0.0
+				};
+				sample_stops(self.stops(), t)
+			}).collect(),
+		}
+	}
+
+	/// change current paint, let them become brighter or darker
+	pub fn brighter(self, factor: f32) -> Self {
+		match self {
+			Self::Solid(t) => Self::Solid(t.brighter(factor)),
+			mut paint => {
+				for stop in paint.stops_mut() {
+					stop.color = stop.color.brighter(factor);
+				}
+				paint
+			}
+		}
+	}
+
+	/// scale gradient geometry with (0,0), see more in [`crate::shape::Shape::pre_scale`]
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		match self {
+			Self::Solid(_) => {},
+			Self::Linear(t) => {
+				t.start = t.start * scale_factor;
+				t.end = t.end * scale_factor;
+			},
+			Self::Radial(t) => {
+				t.center = t.center * scale_factor;
+				t.radius *= scale_factor;
+			},
+			Self::Conic(t) => {
+				t.center = t.center * scale_factor;
+			},
+		}
+	}
+
+	/// get the id used in svg `<defs>`, stays the same for the same paint and position.
+	pub fn svg_id(&self, position: Vec2) -> String {
+		svg_id("nablo_paint", &format!("{:?}{:?}", self, position))
+	}
+
+	/// get gradient definition in svg, returns empty string for [`Paint::Solid`] and [`Paint::Conic`].
+	pub fn svg_def(&self, position: Vec2) -> String {
+		let stops = || {
+			let mut back = String::new();
+			for stop in self.stops() {
+				back = format!("{back}<stop offset=\"{}\" stop-color=\"{}\" stop-opacity=\"{}\"/>",
+					stop.offset,
+					stop.color.svg(),
+					stop.color[3] as f32 / 255.0
+				);
+			}
+			back
+		};
+		match self {
+			Self::Solid(_) | Self::Conic(_) => String::new(),
+			Self::Linear(t) => {
+				let start = t.start + position;
+				let end = t.end + position;
+				format!("<linearGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\" spreadMethod=\"{}\">{}</linearGradient>",
+					self.svg_id(position),
+					start.x,
+					start.y,
+					end.x,
+					end.y,
+					t.spread.svg(),
+					stops()
+				)
+			},
+			Self::Radial(t) => {
+				let center = t.center + position;
+				format!("<radialGradient id=\"{}\" gradientUnits=\"userSpaceOnUse\" cx=\"{}\" cy=\"{}\" r=\"{}\" spreadMethod=\"{}\">{}</radialGradient>",
+					self.svg_id(position),
+					center.x,
+					center.y,
+					t.radius,
+					t.spread.svg(),
+					stops()
+				)
+			},
+		}
+	}
+
+	/// get the value and opacity used in svg `fill` or `stroke` attribute
+	pub fn svg_paint(&self, position: Vec2) -> (String, f32) {
+		match self {
+			Self::Solid(t) => (t.svg(), t[3] as f32 / 255.0),
+			Self::Linear(_) | Self::Radial(_) => (format!("url(#{})", self.svg_id(position)), 1.0),
+			Self::Conic(t) => {
+				let mut sum = [0.0; 4];
+				for stop in &t.stops {
+					for (i, value) in sum.iter_mut().enumerate() {
+						*value += stop.color[i] as f32;
+					}
+				}
+				let len = t.stops.len().max(1) as f32;
+				let color: Color = [(sum[0] / len) as u8, (sum[1] / len) as u8, (sum[2] / len) as u8, (sum[3] / len) as u8].into();
+				(color.svg(), color[3] as f32 / 255.0)
+			},
+		}
+	}
+}
+
+/// make a svg id from `content`, same content always gives the same id, so svg output is reproducible.
+pub(crate) fn svg_id(prefix: &str, content: &str) -> String {
+	// fnv-1a, std hashers may change between rust versions
+	let mut hash: u64 = 0xcbf29ce484222325;
+	for byte in content.bytes() {
+		hash ^= byte as u64;
+		hash = hash.wrapping_mul(0x100000001b3);
+	}
+	format!("{prefix}_{hash:x}")
+}
+
+fn sample_stops(stops: &[GradientStop], t: f32) -> Color {
+	if stops.is_empty() {
+		return [0; 4].into()
+	}
+	if t <= stops[0].offset {
+		return stops[0].color
+	}
+	for window in stops.windows(2) {
+		let (from, to) = (window[0], window[1]);
+		if t <= to.offset {
+			let len = to.offset - from.offset;
+			if len <= 0.0 {
+				return to.color
+			}
+			return from.color.lerp(to.color, (t - from.offset) / len)
+		}
+	}
+	stops[stops.len() - 1].color
+}
+
+/// for [`Paint::Solid`], id = 0, 1, 2 or 3 represents rgba.
+///
+/// for [`Paint::Linear`], id = 0, 1, 2 or 3 represents start.x, start.y, end.x or end.y.
+/// for [`Paint::Radial`], id = 0, 1 or 2 represents center.x, center.y or radius.
+/// for [`Paint::Conic`], id = 0, 1 or 2 represents center.x, center.y or angle.
+/// after that, every stop takes 5 ids, represents offset and rgba.
+impl Animate for Paint {
+	fn animate(&mut self, id: usize, change_to: f32) {
+		let color_value = (compress(change_to) * 255.0) as u8;
+		let geometry_len = self.geometry_len();
+		match self {
+			Self::Solid(t) => {
+				if id < 4 {
+					t[id] = color_value;
+				}
+				return;
+			},
+			Self::Linear(t) => match id {
+				0 => t.start.x = change_to,
+				1 => t.start.y = change_to,
+				2 => t.end.x = change_to,
+				3 => t.end.y = change_to,
+				_ => {},
+			},
+			Self::Radial(t) => match id {
+				0 => t.center.x = change_to,
+				1 => t.center.y = change_to,
+				2 => t.radius = change_to,
+				_ => {},
+			},
+			Self::Conic(t) => match id {
+				0 => t.center.x = change_to,
+				1 => t.center.y = change_to,
+				2 => t.angle = change_to,
+				_ => {},
+			},
+		}
+		if id < geometry_len {
+			return;
+		}
+		let id = id - geometry_len;
+		if let Some(stop) = self.stops_mut().get_mut(id / 5) {
+			match id % 5 {
+				0 => stop.offset = change_to,
+				t => stop.color[t - 1] = color_value,
+			}
+		}
+	}
+
+	fn animate_len(&self) -> usize {
+		self.geometry_len() + self.stops().len() * 5
+	}
+}
+
+impl Default for Style {
+	fn default() -> Self {
+		Self {
+			position: Vec2::default(),
+			transform_origin: Vec2::default(),
+			rotate: 0.0,
+			size: Vec2::NOT_TO_SCALE,
+			fill: [255,255,255,255].into(),
+			stroke_width: 0.0,
+			stroke_color: [0; 4].into(),
+			layer: Layer::Bottom,
+			clip: Area::ZERO,
+			fill_paint: None,
+			stroke_paint: None,
+			line_cap: LineCap::default(),
+			line_join: LineJoin::default(),
+			miter_limit: 4.0,
+			blend_mode: BlendMode::default(),
+			opacity: 1.0,
+			effects: vec!(),
+			clip_masks: vec!(),
+		}
+	}
+}
+
+impl Style {
+	/// how much ids come before paints, new fixed ids should be added below this so ids of paints stay last, see more in [`Style::animate`]
+	pub const FIXED_LEN: usize = 18;
+
+	/// such as color stroke etc.
+	pub fn svg_basic_settings(&self) -> String {
+		let (fill, fill_opacity) = self.get_fill_paint().svg_paint(self.position);
+		let (stroke, stroke_opacity) = self.get_stroke_paint().svg_paint(self.position);
+		format!("stroke-width=\"{}\" stroke=\"{}\" stroke-opacity=\"{}\" stroke-linecap=\"{}\" stroke-linejoin=\"{}\" stroke-miterlimit=\"{}\" opacity=\"{}\" style=\"mix-blend-mode: {}\" fill=\"{}\"  fill-opacity=\"{}\" transform=\"{}\"",
+			self.stroke_width,
+			stroke,
+			stroke_opacity,
+			self.line_cap.svg(),
+			self.line_join.svg(),
+			self.miter_limit,
+			self.opacity,
+			self.blend_mode.svg(),
+			fill,
+			fill_opacity,
+			self.svg_placed_transform(),
+			)
+	}
+
+	/// same as [`Animate`]
+	pub fn animate(&mut self, id: usize, change_to: f32) {
+		fn compress(input: f32) -> f32 {
+			if input > 1.0 {
+				1.0
+			}else if input < 0.0 {
+				0.0
+			}else {
+				input
+			}
+		}
+
+		match id {
+			0 => self.position.x = change_to,
+			1 => self.position.y = change_to,
+			2 => self.transform_origin.x = change_to,
+			3 => self.transform_origin.y = change_to,
+			4 => self.rotate = change_to,
+			5 => self.size.x = change_to,
+			6 => self.size.y = change_to,
+			7 => self.fill[0] = (compress(change_to) * 255.0) as u8,
+			8 => self.fill[1] = (compress(change_to) * 255.0) as u8,
+			9 => self.fill[2] = (compress(change_to) * 255.0) as u8,
+			10 => self.fill[3] = (compress(change_to) * 255.0) as u8,
+			11 => self.stroke_width = change_to,
+			12 => self.stroke_color[0] = (compress(change_to) * 255.0) as u8,
+			13 => self.stroke_color[1] = (compress(change_to) * 255.0) as u8,
+			14 => self.stroke_color[2] = (compress(change_to) * 255.0) as u8,
+			15 => self.stroke_color[3] = (compress(change_to) * 255.0) as u8,
+			16 => self.miter_limit = change_to,
+			17 => self.opacity = compress(change_to),
+			_ => {
+				let mut id = id - Self::FIXED_LEN;
+				if let Some(paint) = &mut self.fill_paint {
+					if id < paint.animate_len() {
+						paint.animate(id, change_to);
+						return;
+					}
+					id -= paint.animate_len();
+				}
+				if let Some(paint) = &mut self.stroke_paint {
+					paint.animate(id, change_to);
+				}
+			}
+		}
+	}
+
+	pub fn len(&self) -> usize { 
+		Self::FIXED_LEN + 
+		self.fill_paint.as_ref().map(|t| t.animate_len()).unwrap_or(0) + 
+		self.stroke_paint.as_ref().map(|t| t.animate_len()).unwrap_or(0)
+	}
+
+	/// get what we are actually filling with
+	pub fn get_fill_paint(&self) -> Paint {
+		self.fill_paint.clone().unwrap_or(Paint::Solid(self.fill))
+	}
+
+	/// get what we are actually stroking with
+	pub fn get_stroke_paint(&self) -> Paint {
+		self.stroke_paint.clone().unwrap_or(Paint::Solid(self.stroke_color))
+	}
+
+	/// transform a point in the same way as [`Area::transform`].
+	pub fn transform_point(&self, point: Vec2) -> Vec2 {
+		point.transfrom_with_center(self.rotate, self.size, self.transform_origin) + self.position
+	}
+
+	/// the inverse of [`Self::transform_point`], returns [`Option::None`] if current style scales to zero.
+	pub fn inverse_transform_point(&self, point: Vec2) -> Option<Vec2> {
+		if self.size.x == 0.0 || self.size.y == 0.0 {
+			return None
+		}
+		Some(((point - self.position - self.transform_origin) / self.size).rotate(-self.rotate) + self.transform_origin)
+	}
+
+	/// svg `transform` attribute value in the same way as [`Area::transform`].
+	pub fn svg_transform(&self) -> String {
+		format!("translate({}) translate({}) scale({}) rotate({}) translate({})",
+			self.position.svg(),
+			self.transform_origin.svg(),
+			self.size.svg(),
+			self.rotate / PI * 180.0,
+			(-self.transform_origin).svg(),
+		)
+	}
+
+	/// same as [`Self::svg_transform`], but for svg elements already placed at [`Self::position`], such as `<rect x y>`.
+	pub fn svg_placed_transform(&self) -> String {
+		let center = self.position + self.transform_origin;
+		format!("translate({}) scale({}) rotate({}) translate({})",
+			center.svg(),
+			self.size.svg(),
+			self.rotate / PI * 180.0,
+			(-center).svg(),
+		)
+	}
+
+	/// get the id of svg `<filter>` made from `effects`
+	pub fn svg_filter_id(&self) -> String {
+		svg_id("nablo_filter", &format!("{:?}", self.effects))
+	}
+
+	fn svg_clip_path_id(&self, len: usize) -> String {
+		svg_id("nablo_clip", &format!("{:?}", &self.clip_masks[..len]))
+	}
+
+	/// put svg of an element after defs of this style, `clip_masks` and `effects` are applied on a `<g>` around the element.
+	///
+	/// the transform stays on the element, so masks and shadows are not rotated or scaled with it, same as vertexs.
+	pub fn svg_wrap(&self, element: String) -> String {
+		let mut attributes = String::new();
+		if !self.clip_masks.is_empty() {
+			attributes = format!("{attributes} clip-path=\"url(#{})\"", self.svg_clip_path_id(self.clip_masks.len()));
+		}
+		if !self.effects.is_empty() {
+			attributes = format!("{attributes} filter=\"url(#{})\"", self.svg_filter_id());
+		}
+		if attributes.is_empty() {
+			format!("{}{}", self.svg_defs(), element)
+		}else {
+			format!("{}<g{}>{}</g>", self.svg_defs(), attributes, element)
+		}
+	}
+
+	/// get svg `<clipPath>`s made from `clip_masks`, every clip path is clipped by the one before it.
+	pub fn svg_clip_paths(&self) -> String {
+		let mut back = String::new();
+		for (i, mask) in self.clip_masks.iter().enumerate() {
+			let clip_path = if i == 0 {
+				String::new()
+			}else {
+				format!(" clip-path=\"url(#{})\"", self.svg_clip_path_id(i))
+			};
+			back = format!("{back}<clipPath id=\"{}\"{clip_path}>{}</clipPath>", self.svg_clip_path_id(i + 1), mask.svg());
+		}
+		back
+	}
+
+	/// clip vertexs with convex clip masks on cpu, other masks are ignored, see more in [`Self::stencil_masks`]
+	#[cfg(feature = "vertexs")]
+	pub fn cpu_clip(&self, vertexs: Vec<Vertex>, indices: Vec<u32>, size: Vec2) -> (Vec<Vertex>, Vec<u32>) {
+		let mut back = (vertexs, indices);
+		for mask in &self.clip_masks {
+			if mask.is_convex() {
+				let polygon: Vec<Vec2> = mask.to_polygon().into_iter().map(|point| Vec2::new(point.x / size.x * 2.0 - 1.0, - (point.y / size.y * 2.0 - 1.0))).collect();
+				back = clip_vertexs(&back.0, &back.1, &polygon);
+			}
+		}
+		back
+	}
+
+	/// geometry of clip masks can not be clipped on cpu, only things covered by every mask should be drawn.
+	#[cfg(feature = "vertexs")]
+	pub fn stencil_masks(&self, size: Vec2) -> Vec<(Vec<Vertex>, Vec<u32>)> {
+		self.clip_masks.iter().filter(|mask| !mask.is_convex()).map(|mask| mask.into_vertexs(size)).collect()
+	}
+
+	/// get svg `<filter>` made from `effects`, shadows are merged under the shape.
+	pub fn svg_filter(&self) -> String {
+		let mut primitives = String::new();
+		let mut shadows = String::new();
+		let mut source = String::from("SourceGraphic");
+		for (i, effect) in self.effects.iter().enumerate() {
+			if let Effect::GaussianBlur(radius) = effect {
+				primitives = format!("{primitives}<feGaussianBlur in=\"{source}\" stdDeviation=\"{}\" result=\"source_{i}\"/>", radius / 2.0);
+				source = format!("source_{i}");
+				continue;
+			}
+			if let Some(shadow) = effect.shadow() {
+				primitives = format!("{primitives}<feGaussianBlur in=\"SourceAlpha\" stdDeviation=\"{}\" result=\"blur_{i}\"/><feOffset in=\"blur_{i}\" dx=\"{}\" dy=\"{}\" result=\"offset_{i}\"/><feFlood flood-color=\"{}\" flood-opacity=\"{}\" result=\"flood_{i}\"/><feComposite in=\"flood_{i}\" in2=\"offset_{i}\" operator=\"in\" result=\"effect_{i}\"/>",
+					shadow.blur / 2.0,
+					shadow.offset.x,
+					shadow.offset.y,
+					shadow.color.svg(),
+					shadow.color[3] as f32 / 255.0,
+				);
+				shadows = format!("{shadows}<feMergeNode in=\"effect_{i}\"/>");
+			}
+		}
+		format!("<filter id=\"{}\" x=\"-50%\" y=\"-50%\" width=\"200%\" height=\"200%\">{primitives}<feMerge>{shadows}<feMergeNode in=\"{source}\"/></feMerge></filter>", self.svg_filter_id())
+	}
+
+	/// paint definitions used by [`Self::svg_basic_settings`], should be placed before the element using them.
+	pub fn svg_defs(&self) -> String {
+		let mut defs = String::new();
+		if let Some(paint) = &self.fill_paint {
+			defs = defs + &paint.svg_def(self.position);
+		}
+		if let Some(paint) = &self.stroke_paint {
+			defs = defs + &paint.svg_def(self.position);
+		}
+		if !self.effects.is_empty() {
+			defs = defs + &self.svg_filter();
+		}
+		defs = defs + &self.svg_clip_paths();
+		if defs.is_empty() {
+			defs
+		}else {
+			format!("<defs>{}</defs>", defs)
+		}
+	}
+}
+
+impl Add for Style {
+	type Output = Self;
+	fn add(self, rhs: Self) -> Self::Output {
+		Self {
+			position: self.position + rhs.position,
+			transform_origin: self.transform_origin + rhs.position,
+			rotate: self.rotate + rhs.rotate,
+			size: self.size + rhs.size,
+			fill: rhs.fill,
+			stroke_width: self.stroke_width + rhs.stroke_width,
+			stroke_color: rhs.stroke_color,
+			..self
+		}
+	}
+}
+
+impl Sub for Style {
+	type Output = Self;
+	fn sub(self, rhs: Self) -> Self::Output {
+		Self {
+			position: self.position - rhs.position,
+			transform_origin: self.transform_origin - rhs.position,
+			rotate: self.rotate - rhs.rotate,
+			size: self.size - rhs.size,
+			stroke_width: self.stroke_width - rhs.stroke_width,
+			..self
+		}
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+/// a circle, uh actually a ellipse if you have applied size.
+pub struct Circle {
+	pub radius: f32,
+}
+
+impl Shape for Circle {
+	fn into_svg(&self, style: &Style) -> String {
+		style.svg_wrap(format!("<ellipse cx=\"{}\" cy=\"{}\" rx=\"{}\" ry=\"{}\" {}/>", 
+			style.position.x + self.radius, 
+			style.position.y + self.radius,
+			self.radius,
+			self.radius,
+			style.svg_basic_settings(),
+			))
+	}
+
+	fn get_area(&self, style: &Style) -> Area {
+		Rect {
 			width_and_height: Vec2::same(2.0 * self.radius),
 			rounding: Vec2::same(self.radius)
 		}.get_area(style)
@@ -571,13 +1742,15 @@ pub struct Rect {
 
 impl Shape for Rect {
 	fn into_svg(&self, style: &Style) -> String {
-		format!("<rect x=\"{}\" y=\"{}\" rx=\"{}\" ry=\"{}\" {} />", 
+		style.svg_wrap(format!("<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"{}\" ry=\"{}\" {} />", 
 			style.position.x, 
 			style.position.y,
+			self.width_and_height.x,
+			self.width_and_height.y,
 			self.rounding.x,
 			self.rounding.y,
 			style.svg_basic_settings()
-			)
+			))
 	}
 
 	fn get_area(&self, style: &Style) -> Area {
@@ -647,7 +1820,7 @@ impl Animate for Rect {
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
 #[serde(default)]
-/// a text. cant be rotated. cant be scale with transform origin. cant even caculate area correctly.
+/// a text, placed by [`Text::layout`] with [`Style::position`] as left top and then transformed like other shapes.
 pub struct Text {
 	pub text: String,
 	/// [`Option::None`] stands for the whole screen
@@ -659,13 +1832,231 @@ pub struct Text {
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
 /// decides how to render text
 pub struct TextStyle {
 	pub is_bold: bool,
-	pub is_italic: bool
+	pub is_italic: bool,
+	/// family name in [`FontRegistry`], [`Option::None`] for the default family.
+	pub font_family: Option<String>,
+	/// font size in pixels, [`Option::None`] for [`EM`]. text is scaled by [`Style::size`] like other shapes.
+	pub font_size: Option<f32>,
+	/// height of a line, times of font size, [`Option::None`] for 1.0.
+	pub line_height: Option<f32>,
+	/// extra space after every character in pixels, refer to css `letter-spacing`.
+	pub letter_spacing: f32,
+	/// extra space after every space in pixels, refer to css `word-spacing`.
+	pub word_spacing: f32,
+	/// how lines are placed in [`Text::text_width`]
+	pub align: TextAlign,
+	/// how lines are placed in [`Text::text_height`]
+	pub vertical_align: TextVerticalAlign,
+	/// how to break lines longer than [`Text::text_width`]
+	pub wrap: TextWrap,
+	/// what to do with text outside [`Text::text_width`] and [`Text::text_height`]
+	pub overflow: TextOverflow,
+	/// lines drawn with text, joined with [`TextSpan::decoration`] in [`RichText`].
+	pub decoration: TextDecoration,
+	/// thickness of decoration lines in pixels, [`Option::None`] for the one suggested by font.
+	pub decoration_thickness: Option<f32>,
+	/// [`Option::None`] for color of text
+	pub decoration_color: Option<Color>,
+}
+
+/// horizontal alignment of text, refer to css `text-align`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+pub enum TextAlign {
+	#[default] Left,
+	Center,
+	Right,
+	/// stretch spaces so every line fills the width, except the last line of a paragraph.
+	Justify,
+}
+
+/// vertical alignment of text, only works when [`Text::text_height`] is not [`Option::None`].
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+pub enum TextVerticalAlign {
+	#[default] Top,
+	Middle,
+	Bottom,
+}
+
+/// how to break lines, only works when [`Text::text_width`] is not [`Option::None`].
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+pub enum TextWrap {
+	/// break between words
+	#[default] Word,
+	/// break between any characters
+	Letter,
+	/// never break, only `\n` starts a new line
+	None,
+}
+
+/// what to do with text outside its box
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+pub enum TextOverflow {
+	/// show everything
+	#[default] Visible,
+	/// hide anything outside [`Text::text_width`] and [`Text::text_height`]
+	Clip,
+	/// replace the end of text with `…` if it does not fit, also clips.
+	Ellipsis,
+	/// replace the middle of text with `…` if it does not fit, also clips.
+	EllipsisMiddle,
+	/// use the largest font size which fits, also clips.
+	ShrinkToFit,
 }
 
 impl TextStyle {
+	/// set font family for current text stlye
+	pub fn set_font_family(self, font_family: Option<String>) -> Self {
+		Self {
+			font_family,
+			..self
+		}
+	}
+
+	/// set font size in pixels for current text stlye
+	pub fn set_font_size(self, font_size: f32) -> Self {
+		Self {
+			font_size: Some(font_size),
+			..self
+		}
+	}
+
+	/// set line height (times of font size) for current text stlye
+	pub fn set_line_height(self, line_height: f32) -> Self {
+		Self {
+			line_height: Some(line_height),
+			..self
+		}
+	}
+
+	/// set letter spacing in pixels for current text stlye
+	pub fn set_letter_spacing(self, letter_spacing: f32) -> Self {
+		Self {
+			letter_spacing,
+			..self
+		}
+	}
+
+	/// set word spacing in pixels for current text stlye
+	pub fn set_word_spacing(self, word_spacing: f32) -> Self {
+		Self {
+			word_spacing,
+			..self
+		}
+	}
+
+	/// set horizontal alignment for current text stlye
+	pub fn set_align(self, align: TextAlign) -> Self {
+		Self {
+			align,
+			..self
+		}
+	}
+
+	/// set vertical alignment for current text stlye
+	pub fn set_vertical_align(self, vertical_align: TextVerticalAlign) -> Self {
+		Self {
+			vertical_align,
+			..self
+		}
+	}
+
+	/// set how to break lines for current text stlye
+	pub fn set_wrap(self, wrap: TextWrap) -> Self {
+		Self {
+			wrap,
+			..self
+		}
+	}
+
+	/// set overflow handling for current text stlye
+	pub fn set_overflow(self, overflow: TextOverflow) -> Self {
+		Self {
+			overflow,
+			..self
+		}
+	}
+
+	/// set decoration lines for current text stlye
+	pub fn set_decoration(self, decoration: TextDecoration) -> Self {
+		Self {
+			decoration,
+			..self
+		}
+	}
+
+	/// set thickness of decoration lines in pixels for current text stlye
+	pub fn set_decoration_thickness(self, decoration_thickness: f32) -> Self {
+		Self {
+			decoration_thickness: Some(decoration_thickness),
+			..self
+		}
+	}
+
+	/// set color of decoration lines for current text stlye
+	pub fn set_decoration_color(self, decoration_color: impl Into<Color>) -> Self {
+		Self {
+			decoration_color: Some(decoration_color.into()),
+			..self
+		}
+	}
+
+	/// get font size in pixels, before [`Style::size`] scales it.
+	pub fn resolved_font_size(&self) -> f32 {
+		self.font_size.unwrap_or(EM * CORRECTION)
+	}
+
+	/// get line height in pixels
+	pub fn resolved_line_height(&self) -> f32 {
+		self.resolved_font_size() * self.line_height.unwrap_or(1.0)
+	}
+
+	/// scale font size and spacings, font size is given after this even if it was [`Option::None`].
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		self.font_size = Some(self.resolved_font_size() * scale_factor);
+		self.letter_spacing *= scale_factor;
+		self.word_spacing *= scale_factor;
+		if let Some(t) = &mut self.decoration_thickness {
+			*t *= scale_factor;
+		}
+	}
+
+	/// get css-like font weight
+	pub fn weight(&self) -> u16 {
+		if self.is_bold {
+			FontRegistry::BOLD
+		}else {
+			FontRegistry::NORMAL
+		}
+	}
+
+	/// get svg attributes of font, `word-spacing` is not included since it changes in justified lines.
+	pub fn svg(&self) -> String {
+		let family = match &self.font_family {
+			Some(t) => format!("font-family=\"{}\" ", t),
+			None => String::new(),
+		};
+		format!("{}font-weight=\"{}\" font-style=\"{}\" letter-spacing=\"{}\"", family, self.weight(), if self.is_italic { "italic" } else { "normal" }, self.letter_spacing)
+	}
+
+	/// get svg `text-decoration` with color and thickness, in form of css shorthand.
+	pub fn svg_decoration(&self) -> String {
+		let mut back = self.decoration.svg();
+		if self.decoration.is_none() {
+			return back
+		}
+		if let Some(t) = &self.decoration_color {
+			back = format!("{back} {}", t.svg());
+		}
+		if let Some(t) = self.decoration_thickness {
+			back = format!("{back} {t}px");
+		}
+		back
+	}
+
 	/// set bold for current text stlye
 	pub fn set_bold(self, is_bold: bool) -> Self {
 		Self {
@@ -674,54 +2065,1548 @@ impl TextStyle {
 		}
 	}
 
-	/// set italic for current text stlye
-	pub fn set_italic(self, is_italic: bool) -> Self {
-		Self {
-			is_italic,
-			..self
+	/// set italic for current text stlye
+	pub fn set_italic(self, is_italic: bool) -> Self {
+		Self {
+			is_italic,
+			..self
+		}
+	}
+}
+
+/// a glyph placed by [`Text::layout`], [`Style::position`] is the origin and transform is not applied.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct LayoutGlyph {
+	pub character: char,
+	/// where the character starts in [`Text::text`], or in all spans joined together for [`RichText`].
+	pub byte_offset: usize,
+	/// which span this glyph belongs to, always 0 for [`Text`]
+	pub span: usize,
+	/// index of font used in layout, fonts of every span are joined together in order of [`FontRegistry::chain`].
+	pub font_index: usize,
+	/// refer to [`fontdue::Font::file_hash`], can be used to find the font in [`FontRegistry`]
+	pub font_hash: usize,
+	/// index of glyph inside its font
+	pub glyph_index: u16,
+	/// font size in pixels
+	pub font_size: f32,
+	/// where the glyph bitmap is
+	pub area: Area,
+	/// pen position on baseline before this glyph
+	pub origin: Vec2,
+	/// how far the pen moves after this glyph, spacings included
+	pub advance: f32,
+	/// which line this glyph is in
+	pub line: usize,
+}
+
+impl LayoutGlyph {
+	/// bytes of this character in [`TextLayout::text`]
+	pub fn byte_range(&self) -> std::ops::Range<usize> {
+		self.byte_offset..self.byte_offset + self.character.len_utf8()
+	}
+}
+
+/// metrics of a font in pixels, y goes up from baseline as fonts do, so things below baseline are negative.
+#[derive(Clone, Copy, Debug, PartialEq, Default)]
+pub struct FontMetrics {
+	pub font_size: f32,
+	/// how far glyphs go above baseline
+	pub ascent: f32,
+	/// how far glyphs go below baseline, usually negative
+	pub descent: f32,
+	/// suggested space between lines
+	pub line_gap: f32,
+	/// height of lowercase letters such as `x`
+	pub x_height: f32,
+	/// height of uppercase letters such as `H`
+	pub cap_height: f32,
+	/// top of underline
+	pub underline_position: f32,
+	pub underline_thickness: f32,
+	/// top of strikethrough
+	pub strikethrough_position: f32,
+	pub strikethrough_thickness: f32,
+}
+
+impl FontMetrics {
+	/// read metrics of `font` in `font_size` pixels, guess those the font does not tell.
+	pub fn new(font: &Font, font_size: f32) -> Self {
+		let (ascent, descent, line_gap) = match font.horizontal_line_metrics(font_size) {
+			Some(t) => (t.ascent, t.descent, t.line_gap),
+			None => (font_size, 0.0, 0.0),
+		};
+		// top of a glyph, if the font has it
+		let top_of = |character: char| if font.lookup_glyph_index(character) == 0 {
+			None
+		}else {
+			let metrics = font.metrics(character, font_size);
+			Some(metrics.height as f32 + metrics.ymin as f32)
+		};
+		let x_height = top_of('x').unwrap_or(ascent / 2.0);
+		let thickness = font_size / 14.0;
+		let mut back = Self {
+			font_size,
+			ascent,
+			descent,
+			line_gap,
+			x_height,
+			cap_height: top_of('H').unwrap_or(ascent * 0.7),
+			underline_position: - font_size / 10.0,
+			underline_thickness: thickness,
+			strikethrough_position: (x_height + thickness) / 2.0,
+			strikethrough_thickness: thickness,
+		};
+		let data = FontRegistry::global().data_by_hash(font.file_hash());
+		if let Some(face) = data.as_ref().and_then(|t| ttf_parser::Face::parse(t, 0).ok()) {
+			let scale = font_size / face.units_per_em() as f32;
+			if let Some(t) = face.x_height() {
+				back.x_height = t as f32 * scale;
+			}
+			if let Some(t) = face.capital_height() {
+				back.cap_height = t as f32 * scale;
+			}
+			if let Some(t) = face.underline_metrics() {
+				back.underline_position = t.position as f32 * scale;
+				back.underline_thickness = t.thickness as f32 * scale;
+			}
+			if let Some(t) = face.strikeout_metrics() {
+				back.strikethrough_position = t.position as f32 * scale;
+				back.strikethrough_thickness = t.thickness as f32 * scale;
+			}
+		}
+		back
+	}
+}
+
+/// a line placed by [`Text::layout`], [`Style::position`] is the origin and transform is not applied.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct LayoutLine {
+	/// bytes of this line in [`Text::text`], line break excluded
+	pub range: std::ops::Range<usize>,
+	/// glyphs of this line in [`TextLayout::glyphs`]
+	pub glyphs: std::ops::Range<usize>,
+	pub top: f32,
+	pub baseline: f32,
+	pub height: f32,
+	/// where the first glyph starts
+	pub left: f32,
+	/// width of the line, trailing whitespaces excluded
+	pub width: f32,
+	/// extra space added to every space by [`TextAlign::Justify`]
+	pub justify_spacing: f32,
+}
+
+/// which line of [`TextDecoration`] a [`LayoutDecoration`] is
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default, Hash)]
+pub enum DecorationKind {
+	#[default] Underline,
+	Overline,
+	Strikethrough,
+}
+
+/// a decoration line placed by [`Text::layout`], [`Style::position`] is the origin and transform is not applied.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct LayoutDecoration {
+	pub kind: DecorationKind,
+	/// the rect to fill
+	pub area: Area,
+	/// [`TextStyle::decoration_color`], [`Option::None`] for color of text
+	pub color: Option<Color>,
+	/// which span this line belongs to, always 0 for [`Text`]
+	pub span: usize,
+	/// which line of text this line is in
+	pub line: usize,
+}
+
+/// result of [`Text::layout`]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct TextLayout {
+	/// the text laid out, differs from [`Text::text`] when [`TextOverflow::Ellipsis`] or [`TextOverflow::EllipsisMiddle`] works.
+	/// byte offsets in this layout refer to it.
+	pub text: String,
+	pub glyphs: Vec<LayoutGlyph>,
+	pub lines: Vec<LayoutLine>,
+	/// underlines, overlines and strikethroughs, one for every run of glyphs in the same span of a line
+	pub decorations: Vec<LayoutDecoration>,
+	/// metrics of the first font of every span, always one for [`Text`]
+	pub metrics: Vec<FontMetrics>,
+	/// where all lines are, clip included
+	pub area: Area,
+	/// anything outside should be hidden, only given when [`TextStyle::overflow`] is [`TextOverflow::Clip`].
+	pub clip: Option<Area>,
+	/// font size of [`Text::text_style`], may be shrinked by [`TextOverflow::ShrinkToFit`]
+	pub font_size: f32,
+	pub line_height: f32,
+}
+
+impl TextLayout {
+	/// which line a byte offset is in, offsets at wrapped line breaks belong to the later line.
+	pub fn line_at(&self, byte_offset: usize) -> usize {
+		self.lines.iter().rposition(|t| t.range.start <= byte_offset).unwrap_or(0)
+	}
+
+	/// where the caret before `byte_offset` is, a zero-width area from top to bottom of its line.
+	pub fn caret(&self, byte_offset: usize) -> Area {
+		match self.lines.get(self.line_at(byte_offset)) {
+			Some(line) => {
+				let x = self.caret_x(line, byte_offset);
+				Area::new(Vec2::new(x, line.top), Vec2::new(x, line.top + line.height))
+			},
+			None => Area::ZERO,
+		}
+	}
+
+	fn caret_x(&self, line: &LayoutLine, byte_offset: usize) -> f32 {
+		let glyphs = &self.glyphs[line.glyphs.clone()];
+		match glyphs.iter().find(|t| t.byte_offset >= byte_offset) {
+			Some(t) => t.origin.x,
+			None => match glyphs.last() {
+				Some(t) => t.origin.x + t.advance,
+				None => line.left,
+			},
+		}
+	}
+
+	/// find the nearest caret position to `point`, returns a byte offset.
+	pub fn hit(&self, point: Vec2) -> usize {
+		let line = match self.lines.iter().position(|t| point.y < t.top + t.height) {
+			Some(t) => &self.lines[t],
+			None => match self.lines.last() {
+				Some(t) => t,
+				None => return 0,
+			},
+		};
+		let glyphs = &self.glyphs[line.glyphs.clone()];
+		for glyph in glyphs {
+			if point.x < glyph.origin.x + glyph.advance / 2.0 {
+				return glyph.byte_offset
+			}
+		}
+		// stay before the space at end of a wrapped line, otherwise the caret jumps to next line
+		match glyphs.last() {
+			Some(t) if t.character.is_whitespace() && line.range.end == t.byte_offset + t.character.len_utf8() => t.byte_offset,
+			_ => line.range.end,
+		}
+	}
+
+	/// areas to highlight when `range` (in bytes) is selected, one for every line.
+	pub fn selection(&self, range: std::ops::Range<usize>) -> Vec<Area> {
+		let mut back = vec!();
+		for line in &self.lines {
+			let start = range.start.max(line.range.start);
+			let end = range.end.min(line.range.end);
+			if start >= end {
+				continue;
+			}
+			let left = self.caret_x(line, start);
+			let right = self.caret_x(line, end);
+			back.push(Area::new(Vec2::new(left, line.top), Vec2::new(right, line.top + line.height)));
+		}
+		back
+	}
+}
+
+/// a command of [`Outline`], refer to svg path commands.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Copy, Debug, PartialEq)]
+pub enum PathCommand {
+	/// starts a new contour
+	MoveTo(Vec2),
+	LineTo(Vec2),
+	/// control point and end point
+	QuadTo(Vec2, Vec2),
+	/// two control points and end point
+	CubicTo(Vec2, Vec2, Vec2),
+	/// closes current contour
+	Close,
+}
+
+/// outline of a glyph, made of contours.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct Outline {
+	pub commands: Vec<PathCommand>,
+	/// the character of this glyph
+	pub character: char,
+}
+
+impl Outline {
+	/// move every point by `delta`
+	pub fn move_delta_to(&mut self, delta: Vec2) {
+		self.map_points(|point| point + delta);
+	}
+
+	/// move every point, control points included, to where `map` gives
+	pub fn map_points(&mut self, map: impl Fn(Vec2) -> Vec2) {
+		for command in &mut self.commands {
+			match command {
+				PathCommand::MoveTo(t) | PathCommand::LineTo(t) => *t = map(*t),
+				PathCommand::QuadTo(t1, t2) => {
+					*t1 = map(*t1);
+					*t2 = map(*t2);
+				},
+				PathCommand::CubicTo(t1, t2, t3) => {
+					*t1 = map(*t1);
+					*t2 = map(*t2);
+					*t3 = map(*t3);
+				},
+				PathCommand::Close => {},
+			}
+		}
+	}
+
+	/// get `d` of svg `<path>`
+	pub fn svg(&self) -> String {
+		let mut back = String::new();
+		for command in &self.commands {
+			let command = match command {
+				PathCommand::MoveTo(t) => format!("M {}", t.svg()),
+				PathCommand::LineTo(t) => format!("L {}", t.svg()),
+				PathCommand::QuadTo(t1, t2) => format!("Q {} {}", t1.svg(), t2.svg()),
+				PathCommand::CubicTo(t1, t2, t3) => format!("C {} {} {}", t1.svg(), t2.svg(), t3.svg()),
+				PathCommand::Close => "Z".to_string(),
+			};
+			if back.is_empty() {
+				back = command;
+			}else {
+				back = format!("{back} {command}");
+			}
+		}
+		back
+	}
+
+	/// flatten every contour into a polygon, curves are cut into lines no further than `tolerance` from them.
+	pub fn to_polygons(&self, tolerance: f32) -> Vec<Polygon> {
+		// enough segments to keep error of a curve in tolerance, estimated by its control polygon
+		let segments = |length: f32| ((length / tolerance.max(0.001)).sqrt().ceil() as usize).clamp(1, 64);
+		let mut back = vec!();
+		let mut current: Vec<Vec2> = vec!();
+		let mut pen = Vec2::ZERO;
+		for command in &self.commands {
+			match *command {
+				PathCommand::MoveTo(t) => {
+					if current.len() >= 3 {
+						back.push(std::mem::take(&mut current).into());
+					}
+					current.clear();
+					current.push(t);
+					pen = t;
+				},
+				PathCommand::LineTo(t) => {
+					current.push(t);
+					pen = t;
+				},
+				PathCommand::QuadTo(control, to) => {
+					let count = segments((control - pen).len() + (to - control).len());
+					for i in 1..=count {
+						let t = i as f32 / count as f32;
+						current.push(pen * ((1.0 - t) * (1.0 - t)) + control * (2.0 * t * (1.0 - t)) + to * (t * t));
+					}
+					pen = to;
+				},
+				PathCommand::CubicTo(control_one, control_two, to) => {
+					let count = segments((control_one - pen).len() + (control_two - control_one).len() + (to - control_two).len());
+					let from = pen;
+					for i in 1..=count {
+						let t = i as f32 / count as f32;
+						let s = 1.0 - t;
+						current.push(from * (s * s * s) + control_one * (3.0 * s * s * t) + control_two * (3.0 * s * t * t) + to * (t * t * t));
+					}
+					pen = to;
+				},
+				PathCommand::Close => {
+					if current.len() >= 3 {
+						back.push(std::mem::take(&mut current).into());
+					}
+					current.clear();
+				},
+			}
+		}
+		if current.len() >= 3 {
+			back.push(current.into());
+		}
+		back
+	}
+}
+
+/// collects outline of a glyph from [`ttf_parser`] in font units, y goes up.
+struct OutlineCollector {
+	commands: Vec<PathCommand>,
+	origin: Vec2,
+	scale: f32,
+}
+
+impl OutlineCollector {
+	fn point(&self, x: f32, y: f32) -> Vec2 {
+		self.origin + Vec2::new(x * self.scale, - y * self.scale)
+	}
+}
+
+impl ttf_parser::OutlineBuilder for OutlineCollector {
+	fn move_to(&mut self, x: f32, y: f32) {
+		let point = self.point(x, y);
+		self.commands.push(PathCommand::MoveTo(point));
+	}
+
+	fn line_to(&mut self, x: f32, y: f32) {
+		let point = self.point(x, y);
+		self.commands.push(PathCommand::LineTo(point));
+	}
+
+	fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+		let command = PathCommand::QuadTo(self.point(x1, y1), self.point(x, y));
+		self.commands.push(command);
+	}
+
+	fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+		let command = PathCommand::CubicTo(self.point(x1, y1), self.point(x2, y2), self.point(x, y));
+		self.commands.push(command);
+	}
+
+	fn close(&mut self) {
+		self.commands.push(PathCommand::Close);
+	}
+}
+
+/// outlines of every glyph in `layout`, glyphs without outline or font file are left out.
+fn layout_outlines(layout: &TextLayout) -> Vec<Outline> {
+	glyph_outlines(&layout.glyphs, |_, point| point)
+}
+
+/// outlines of `glyphs`, every point is moved by `place` with index of its glyph, see [`PathGlyph::place`].
+fn glyph_outlines(glyphs: &[LayoutGlyph], place: impl Fn(usize, Vec2) -> Vec2) -> Vec<Outline> {
+	let mut datas: Vec<(usize, Arc<Vec<u8>>)> = vec!();
+	{
+		let registry = FontRegistry::global();
+		for glyph in glyphs {
+			if !datas.iter().any(|t| t.0 == glyph.font_hash) {
+				if let Some(data) = registry.data_by_hash(glyph.font_hash) {
+					datas.push((glyph.font_hash, data));
+				}
+			}
+		}
+	}
+	let faces: Vec<(usize, ttf_parser::Face)> = datas.iter().filter_map(|(hash, data)| Some((*hash, ttf_parser::Face::parse(data, 0).ok()?))).collect();
+	let mut back = vec!();
+	for (index, glyph) in glyphs.iter().enumerate() {
+		let face = match faces.iter().find(|t| t.0 == glyph.font_hash) {
+			Some(t) => &t.1,
+			None => continue,
+		};
+		let mut collector = OutlineCollector {
+			commands: vec!(),
+			origin: glyph.origin,
+			scale: glyph.font_size / face.units_per_em() as f32,
+		};
+		if face.outline_glyph(ttf_parser::GlyphId(glyph.glyph_index), &mut collector).is_some() {
+			let mut outline = Outline {
+				commands: collector.commands,
+				character: glyph.character,
+			};
+			outline.map_points(|point| place(index, point));
+			back.push(outline);
+		}
+	}
+	back
+}
+
+impl Text {
+	/// place every glyph of this text, returns a error when there is no font to layout with.
+	///
+	/// this is the layout [`Shape::get_area`] and [`Shape::into_vertexs`] use, so glyphs found here are exactly where they are drawn before transform.
+	pub fn layout(&self) -> Result<TextLayout, FontError> {
+		layout_spans(&self.text, &[(0..self.text.len(), &self.text_style)], &self.text_style, self.text_width, self.text_height)
+	}
+
+	/// where the caret before the `index`th character is, a zero-width area from top to bottom of its line.
+	///
+	/// indices of characters in caret functions refer to [`TextLayout::text`], which is [`Self::text`] unless a ellipsis is shown.
+	pub fn caret_area(&self, style: &Style, index: usize) -> Result<Area, FontError> {
+		let layout = self.layout()?;
+		Ok(layout.caret(byte_of(&layout.text, index)).transform(style))
+	}
+
+	/// find the nearest caret position to `point`, returns index of character.
+	pub fn index_at(&self, style: &Style, point: Vec2) -> Result<usize, FontError> {
+		let layout = self.layout()?;
+		let point = match style.inverse_transform_point(point) {
+			Some(t) => t,
+			None => return Ok(0),
+		};
+		Ok(index_of(&layout.text, layout.hit(point)))
+	}
+
+	/// areas to highlight when characters in `range` are selected, one for every line.
+	pub fn selection_areas(&self, style: &Style, range: std::ops::Range<usize>) -> Result<Vec<Area>, FontError> {
+		let layout = self.layout()?;
+		let range = byte_of(&layout.text, range.start)..byte_of(&layout.text, range.end);
+		Ok(layout.selection(range).into_iter().map(|t| t.transform(style)).collect())
+	}
+
+	/// which line the `index`th character is in, wrapped lines included.
+	pub fn line_of(&self, index: usize) -> Result<usize, FontError> {
+		let layout = self.layout()?;
+		Ok(layout.line_at(byte_of(&layout.text, index)))
+	}
+
+	/// indices of characters in the line which the `index`th character is in, useful for `Home` and `End` keys. line breaks are excluded.
+	pub fn line_range(&self, index: usize) -> Result<std::ops::Range<usize>, FontError> {
+		let layout = self.layout()?;
+		match layout.lines.get(layout.line_at(byte_of(&layout.text, index))) {
+			Some(line) => Ok(index_of(&layout.text, line.range.start)..index_of(&layout.text, line.range.end)),
+			None => Ok(0..0),
+		}
+	}
+
+	/// scale font size, spacings and the box, see more in [`TextStyle::pre_scale`].
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		self.text_style.pre_scale(scale_factor);
+		self.text_width = self.text_width.map(|t| t * scale_factor);
+		self.text_height = self.text_height.map(|t| t * scale_factor);
+	}
+
+	/// underlines, overlines and strikethroughs of this text, placed by the same layout as [`Shape::get_area`] with [`Style::position`] as origin, transform is not applied.
+	pub fn decorations(&self) -> Result<Vec<LayoutDecoration>, FontError> {
+		Ok(self.layout()?.decorations)
+	}
+
+	/// metrics of the first font of [`Self::text_style`], in the font size used by [`Self::layout`].
+	pub fn metrics(&self) -> Result<FontMetrics, FontError> {
+		Ok(self.layout()?.metrics.first().copied().unwrap_or_default())
+	}
+
+	/// outlines of every glyph, placed by the same layout as [`Shape::get_area`] with [`Style::position`] as origin, transform is not applied.
+	///
+	/// glyphs in fonts without [`crate::shape::registry::FontFace::data`] are left out, [`TextOverflow::Clip`] does not cut outlines.
+	pub fn to_outlines(&self) -> Result<Vec<Outline>, FontError> {
+		Ok(layout_outlines(&self.layout()?))
+	}
+
+	/// same as [`Self::to_outlines`], but flattened into polygons, every contour is a polygon. holes are polygons in opposite direction.
+	///
+	/// curves are cut into lines no further than `tolerance` pixels from them.
+	pub fn to_polygons(&self, tolerance: f32) -> Result<Vec<Polygon>, FontError> {
+		Ok(self.to_outlines()?.iter().flat_map(|t| t.to_polygons(tolerance)).collect())
+	}
+
+	/// same as [`Shape::get_area`], but returns a error when there is no font to layout with instead of a empty area.
+	pub fn try_area(&self, style: &Style) -> Result<Area, FontError> {
+		let layout = self.layout()?;
+		Ok(layout.area.transform(style))
+	}
+}
+
+/// place `text` as one flow, `spans` are ranges in `text` with their own styles, `base` decides alignment, wrapping, overflow and height of empty lines.
+///
+/// layouts are reused from [`LayoutCache::global`] when possible.
+fn layout_spans(text: &str, spans: &[(std::ops::Range<usize>, &TextStyle)], base: &TextStyle, text_width: Option<f32>, text_height: Option<f32>) -> Result<TextLayout, FontError> {
+	let key = LayoutKey {
+		text: text.to_string(),
+		spans: spans.iter().map(|(range, text_style)| (range.clone(), (*text_style).clone())).collect(),
+		base: base.clone(),
+		text_width,
+		text_height,
+		generation: FontRegistry::global().generation(),
+	};
+	if let Some(layout) = LayoutCache::global().get(&key) {
+		return Ok(layout)
+	}
+	// do not hold the cache while laying out, other threads may want it
+	let layout = layout_fitted(text, spans, base, text_width, text_height)?;
+	LayoutCache::global().insert(key, layout.clone());
+	Ok(layout)
+}
+
+/// text is elided or shrinked to fit `text_width` and `text_height` here, see more in [`TextOverflow`] and [`layout_spans`].
+fn layout_fitted(text: &str, spans: &[(std::ops::Range<usize>, &TextStyle)], base: &TextStyle, text_width: Option<f32>, text_height: Option<f32>) -> Result<TextLayout, FontError> {
+	let layout = layout_flow(text, spans, base, text_width, text_height)?;
+	let fits = |layout: &TextLayout| {
+		let width = layout.lines.iter().map(|t| t.width).fold(0.0, f32::max);
+		let height: f32 = layout.lines.iter().map(|t| t.height).sum();
+		// allow a little error from float
+		text_width.is_none_or(|t| width <= t + 0.01) && text_height.is_none_or(|t| height <= t + 0.01)
+	};
+	if fits(&layout) {
+		return Ok(layout)
+	}
+	match base.overflow {
+		TextOverflow::Visible | TextOverflow::Clip => Ok(layout),
+		TextOverflow::ShrinkToFit => {
+			// search for the largest scale which fits
+			let (mut low, mut high) = (0.0, 1.0);
+			let mut best = None;
+			for _ in 0..12 {
+				let scale = (low + high) / 2.0;
+				let scaled: Vec<TextStyle> = spans.iter().map(|t| t.1).chain(std::iter::once(base)).map(|text_style| {
+					let mut text_style = text_style.clone();
+					text_style.font_size = Some(text_style.resolved_font_size() * scale);
+					text_style.letter_spacing *= scale;
+					text_style.word_spacing *= scale;
+					text_style.decoration_thickness = text_style.decoration_thickness.map(|t| t * scale);
+					text_style
+				}).collect();
+				let scaled_spans: Vec<(std::ops::Range<usize>, &TextStyle)> = spans.iter().zip(scaled.iter()).map(|(t, text_style)| (t.0.clone(), text_style)).collect();
+				let layout = layout_flow(text, &scaled_spans, &scaled[spans.len()], text_width, text_height)?;
+				if fits(&layout) {
+					low = scale;
+					best = Some((layout, true));
+				}else {
+					high = scale;
+					// keep the smallest one until something fits
+					if !best.as_ref().is_some_and(|t| t.1) {
+						best = Some((layout, false));
+					}
+				}
+			}
+			Ok(best.map_or(layout, |t| t.0))
+		},
+		TextOverflow::Ellipsis | TextOverflow::EllipsisMiddle => {
+			let is_middle = base.overflow == TextOverflow::EllipsisMiddle;
+			let len = utf8_slice::len(text);
+			// search for how many characters can be kept
+			let (mut low, mut high) = (0, len);
+			let mut best = None;
+			while low < high {
+				let keep = (low + high).div_ceil(2);
+				let layout = layout_elided(text, spans, base, text_width, text_height, keep, is_middle)?;
+				if fits(&layout) {
+					low = keep;
+					best = Some(layout);
+				}else {
+					high = keep - 1;
+				}
+			}
+			match best {
+				Some(t) => Ok(t),
+				None => layout_elided(text, spans, base, text_width, text_height, 0, is_middle),
+			}
+		},
+	}
+}
+
+/// layout `text` with only `keep` characters and a `…`, which is at end or in middle.
+#[allow(clippy::too_many_arguments)]
+fn layout_elided(text: &str, spans: &[(std::ops::Range<usize>, &TextStyle)], base: &TextStyle, text_width: Option<f32>, text_height: Option<f32>, keep: usize, is_middle: bool) -> Result<TextLayout, FontError> {
+	const ELLIPSIS: &str = "…";
+	let (head, tail) = if is_middle { (keep.div_ceil(2), keep / 2) } else { (keep, 0) };
+	let head_end = utf8_slice::till(text, head).len();
+	let tail_start = text.len() - utf8_slice::from(text, utf8_slice::len(text) - tail).len();
+	let elided = format!("{}{}{}", &text[..head_end], ELLIPSIS, &text[tail_start..]);
+	// where tail moves to
+	let shift = head_end + ELLIPSIS.len();
+	let mut elided_spans = vec!();
+	// which original span every elided span is
+	let mut origins = vec!();
+	for (i, (range, text_style)) in spans.iter().enumerate() {
+		let head_range = range.start.min(head_end)..range.end.min(head_end);
+		let ellipsis_here = range.contains(&head_end.saturating_sub(1)) || (head_end == 0 && i == 0);
+		let head_range = if ellipsis_here { head_range.start..shift } else { head_range };
+		if !head_range.is_empty() {
+			elided_spans.push((head_range, *text_style));
+			origins.push(i);
+		}
+		let tail_range = range.start.max(tail_start)..range.end.max(tail_start);
+		if !tail_range.is_empty() {
+			elided_spans.push((tail_range.start - tail_start + shift..tail_range.end - tail_start + shift, *text_style));
+			origins.push(i);
+		}
+		// spans elided entirely are kept empty, so they still have metrics
+		if origins.last() != Some(&i) {
+			elided_spans.push((0..0, *text_style));
+			origins.push(i);
+		}
+	}
+	let mut layout = layout_flow(&elided, &elided_spans, base, text_width, text_height)?;
+	for glyph in &mut layout.glyphs {
+		glyph.span = origins.get(glyph.span).copied().unwrap_or(0);
+	}
+	for decoration in &mut layout.decorations {
+		decoration.span = origins.get(decoration.span).copied().unwrap_or(0);
+	}
+	layout.metrics = (0..spans.len()).filter_map(|i| origins.iter().position(|t| *t == i).map(|t| layout.metrics[t])).collect();
+	Ok(layout)
+}
+
+/// place `text` as one flow without caring about overflow, see more in [`layout_spans`].
+fn layout_flow(text: &str, spans: &[(std::ops::Range<usize>, &TextStyle)], base: &TextStyle, text_width: Option<f32>, text_height: Option<f32>) -> Result<TextLayout, FontError> {
+	// fonts of every span are put together, chains are indices of it
+	let mut fonts: Vec<Arc<Font>> = vec!();
+	let mut chains: Vec<Vec<usize>> = vec!();
+	{
+		let registry = FontRegistry::global();
+		for text_style in spans.iter().map(|t| t.1).chain(std::iter::once(base)) {
+			let chain = registry.try_chain(text_style)?.into_iter().map(|font| match fonts.iter().position(|t| Arc::ptr_eq(t, &font)) {
+				Some(i) => i,
+				None => {
+					fonts.push(font);
+					fonts.len() - 1
+				},
+			}).collect();
+			chains.push(chain);
+		}
+	}
+	let mut metrics: Vec<FontMetrics> = spans.iter().map(|t| t.1).chain(std::iter::once(base)).zip(chains.iter()).map(|(text_style, chain)| {
+		FontMetrics::new(&fonts[chain[0]], text_style.resolved_font_size())
+	}).collect();
+	// how much a line takes above and below baseline for every span, refer to css half-leading
+	let vertical_metrics: Vec<(f32, f32)> = spans.iter().map(|t| t.1).chain(std::iter::once(base)).zip(metrics.iter()).map(|(text_style, metrics)| {
+		let line_height = text_style.resolved_line_height();
+		let above = (line_height - metrics.ascent + metrics.descent) / 2.0 + metrics.ascent;
+		(above, line_height - above)
+	}).collect();
+	metrics.pop();
+	let span_of = |byte_offset: usize| spans.iter().position(|t| t.0.contains(&byte_offset)).unwrap_or(0);
+	// fontdue does not know spacings, so lines are broken here instead
+	let settings = fontdue::layout::LayoutSettings::default();
+	let max_width = if base.wrap == TextWrap::None { None } else { text_width };
+	let mut back = TextLayout {
+		text: text.to_string(),
+		metrics,
+		font_size: base.resolved_font_size(),
+		line_height: base.resolved_line_height(),
+		..Default::default()
+	};
+	let mut layout = fontdue::layout::Layout::new(fontdue::layout::CoordinateSystem::PositiveYDown);
+	let mut paragraph_start = 0;
+	let mut line_top = 0.0;
+	// lines can not be justified at end of paragraphs
+	let mut paragraph_ends = vec!();
+	for paragraph in text.split('\n') {
+		let paragraph_end = paragraph_start + paragraph.len();
+		layout.reset(&settings);
+		for (i, (range, text_style)) in spans.iter().enumerate() {
+			let start = range.start.max(paragraph_start);
+			let end = range.end.min(paragraph_end);
+			if start < end {
+				append_run(&mut layout, &fonts, &chains[i], &text[start..end], text_style.resolved_font_size(), start);
+			}
+		}
+		let glyphs = layout.glyphs();
+		let hard_lines: Vec<(usize, usize)> = match layout.lines() {
+			Some(lines) => lines.iter().map(|t| (t.glyph_start, t.glyph_end + 1)).collect(),
+			None => vec!((0, 0)),
+		};
+		// metrics, pen position with spacings and advance of every glyph
+		let mut placed: Vec<(fontdue::Metrics, f32, f32)> = Vec::with_capacity(glyphs.len());
+		let mut lines = vec!();
+		for (hard_start, hard_end) in hard_lines {
+			// spacings added so far in this line, glyphs are placed by fontdue and then moved by it
+			let mut spacing = 0.0;
+			for glyph in &glyphs[hard_start..hard_end] {
+				let text_style = spans[span_of(glyph.user_data + glyph.byte_offset)].1;
+				let metrics = if glyph.char_data.is_control() {
+					fontdue::Metrics::default()
+				}else {
+					fonts[glyph.font_index].metrics_indexed(glyph.key.glyph_index, glyph.key.px)
+				};
+				let mut extra = text_style.letter_spacing;
+				if glyph.parent == ' ' {
+					extra += text_style.word_spacing;
+				}
+				// same as how fontdue moves its pen
+				placed.push((metrics, glyph.x - metrics.xmin as f32 + spacing, metrics.advance_width.ceil() + extra));
+				spacing += extra;
+			}
+			// break before the glyph which goes beyond `max_width`, at the last chance we have
+			let mut line_start = hard_start;
+			let mut chance = None;
+			let mut index = hard_start;
+			while index < hard_end {
+				let glyph = &glyphs[index];
+				let is_visible = !glyph.char_data.is_whitespace() && !glyph.char_data.is_control();
+				if index > line_start && (base.wrap == TextWrap::Letter || (glyphs[index - 1].char_data.is_whitespace() && is_visible)) {
+					chance = Some(index);
+				}
+				let (_, pen, advance) = placed[index];
+				if max_width.is_some_and(|t| index > line_start && is_visible && pen + advance - placed[line_start].1 > t + 0.01) {
+					let at = chance.unwrap_or(index);
+					lines.push((line_start, at));
+					line_start = at;
+					chance = None;
+					index = at;
+					continue;
+				}
+				index += 1;
+			}
+			lines.push((line_start, hard_end));
+		}
+		for (i, (glyph_start, glyph_end)) in lines.iter().enumerate() {
+			let line = back.lines.len();
+			let first_glyph = back.glyphs.len();
+			let line_left = placed.get(*glyph_start).map_or(0.0, |t| t.1);
+			let mut width: f32 = 0.0;
+			let (mut above, mut below) = if glyph_start == glyph_end { vertical_metrics[spans.len()] } else { (0.0, 0.0) };
+			for (glyph, (metrics, pen, advance)) in glyphs[*glyph_start..*glyph_end].iter().zip(&placed[*glyph_start..*glyph_end]) {
+				let byte_offset = glyph.user_data + glyph.byte_offset;
+				let span = span_of(byte_offset);
+				above = above.max(vertical_metrics[span].0);
+				below = below.max(vertical_metrics[span].1);
+				let advance = *advance;
+				// baseline is at 0 for now
+				let origin = Vec2::new(pen - line_left, 0.0);
+				let left_top = origin + Vec2::new(metrics.xmin as f32, - metrics.ymin as f32 - metrics.height as f32);
+				back.glyphs.push(LayoutGlyph {
+					character: glyph.parent,
+					byte_offset,
+					span,
+					font_index: glyph.font_index,
+					font_hash: glyph.key.font_hash,
+					glyph_index: glyph.key.glyph_index,
+					font_size: glyph.key.px,
+					area: Area::new(left_top, left_top + Vec2::new(metrics.width as f32, metrics.height as f32)),
+					origin,
+					advance,
+					line,
+				});
+				if !glyph.char_data.is_whitespace() && !glyph.char_data.is_control() {
+					width = origin.x + advance;
+				}
+			}
+			let baseline = line_top + above;
+			for glyph in &mut back.glyphs[first_glyph..] {
+				glyph.origin.y += baseline;
+				glyph.area.move_delta_to(Vec2::new(0.0, baseline));
+			}
+			let start = match back.glyphs.get(first_glyph) {
+				Some(t) => t.byte_offset,
+				None => paragraph_start,
+			};
+			let end = match lines.get(i + 1) {
+				Some((next, _)) => glyphs[*next].user_data + glyphs[*next].byte_offset,
+				None => paragraph_end,
+			};
+			back.lines.push(LayoutLine {
+				range: start..end,
+				glyphs: first_glyph..back.glyphs.len(),
+				top: line_top,
+				baseline,
+				height: above + below,
+				left: 0.0,
+				width,
+				justify_spacing: 0.0,
+			});
+			line_top += above + below;
+			paragraph_ends.push(i + 1 == lines.len());
+		}
+		paragraph_start = paragraph_end + 1;
+	}
+	let content = Vec2::new(back.lines.iter().map(|t| t.width).fold(0.0, f32::max), line_top);
+	let container = Vec2::new(text_width.unwrap_or(content.x), text_height.unwrap_or(content.y));
+	let top = match base.vertical_align {
+		TextVerticalAlign::Top => 0.0,
+		TextVerticalAlign::Middle => (container.y - content.y) / 2.0,
+		TextVerticalAlign::Bottom => container.y - content.y,
+	};
+	for (line, is_paragraph_end) in back.lines.iter_mut().zip(paragraph_ends) {
+		let free = container.x - line.width;
+		let glyphs = &mut back.glyphs[line.glyphs.clone()];
+		// extra space for every space in justified lines
+		let mut stretch = 0.0;
+		line.left = match base.align {
+			TextAlign::Left => 0.0,
+			TextAlign::Center => free / 2.0,
+			TextAlign::Right => free,
+			TextAlign::Justify => {
+				let spaces = glyphs.iter().filter(|t| t.character == ' ' && t.origin.x < line.width).count();
+				if !is_paragraph_end && spaces > 0 && free > 0.0 {
+					stretch = free / spaces as f32;
+					line.width = container.x;
+					line.justify_spacing = stretch;
+				}
+				0.0
+			},
+		};
+		line.top += top;
+		line.baseline += top;
+		let mut shift = Vec2::new(line.left, top);
+		for glyph in glyphs {
+			glyph.origin = glyph.origin + shift;
+			glyph.area.move_delta_to(shift);
+			if glyph.character == ' ' && stretch != 0.0 {
+				glyph.advance += stretch;
+				shift.x += stretch;
+			}
+		}
+	}
+	// decorations are placed after alignment, so justified spaces are covered
+	for (line_index, line) in back.lines.iter().enumerate() {
+		let glyphs = &back.glyphs[line.glyphs.clone()];
+		let mut start = 0;
+		while start < glyphs.len() {
+			let span = glyphs[start].span;
+			let end = glyphs[start..].iter().position(|t| t.span != span).map_or(glyphs.len(), |t| t + start);
+			let left = glyphs[start].origin.x;
+			// trailing spaces of a line are not decorated
+			let right = (glyphs[end - 1].origin.x + glyphs[end - 1].advance).min(line.left + line.width);
+			start = end;
+			let (text_style, metrics) = match (spans.get(span), back.metrics.get(span)) {
+				(Some((_, text_style)), Some(metrics)) => (text_style, metrics),
+				_ => continue,
+			};
+			if right <= left || text_style.decoration.is_none() {
+				continue;
+			}
+			// tops below baseline
+			for (kind, is_on, top, thickness) in [
+				(DecorationKind::Underline, text_style.decoration.underline, - metrics.underline_position, metrics.underline_thickness),
+				(DecorationKind::Overline, text_style.decoration.overline, - metrics.ascent, metrics.underline_thickness),
+				(DecorationKind::Strikethrough, text_style.decoration.strikethrough, - metrics.strikethrough_position, metrics.strikethrough_thickness),
+			] {
+				if !is_on {
+					continue;
+				}
+				let thickness = text_style.decoration_thickness.unwrap_or(thickness);
+				back.decorations.push(LayoutDecoration {
+					kind,
+					area: Area::new(Vec2::new(left, line.baseline + top), Vec2::new(right, line.baseline + top + thickness)),
+					color: text_style.decoration_color,
+					span,
+					line: line_index,
+				});
+			}
+		}
+	}
+	let left = back.lines.iter().map(|t| t.left).fold(f32::INFINITY, f32::min);
+	let right = back.lines.iter().map(|t| t.left + t.width).fold(f32::NEG_INFINITY, f32::max);
+	back.area = Area::new(Vec2::new(left, top), Vec2::new(right, top + content.y));
+	if base.overflow != TextOverflow::Visible {
+		// sides without limit are not clipped
+		let clip = Area::new(
+			Vec2::new(if text_width.is_some() { 0.0 } else { left }, if text_height.is_some() { 0.0 } else { top }),
+			Vec2::new(text_width.unwrap_or(right), text_height.unwrap_or(top + content.y)),
+		);
+		back.area = back.area.cross_part(&clip);
+		back.clip = Some(clip);
+	}
+	Ok(back)
+}
+
+/// byte offset of the `index`th character, clamped to the end.
+fn byte_of(text: &str, index: usize) -> usize {
+	utf8_slice::till(text, index).len()
+}
+
+/// index of character at byte offset
+fn index_of(text: &str, byte_offset: usize) -> usize {
+	utf8_slice::len(&text[..byte_offset.min(text.len())])
+}
+
+/// escape text to put inside svg
+fn escape_svg(text: &str) -> String {
+	text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+/// put svg `<text>` inside a clip if needed, defs, clip masks and effects of `style` included.
+fn svg_text_clip(clip: Option<Area>, style: &Style, text: String) -> String {
+	match clip {
+		Some(clip) => {
+			let transform = style.svg_placed_transform();
+			// the same box placed in other ways needs its own clip
+			let id = svg_id("nablo_text_clip", &format!("{:?}{:?}{}", style.position, clip, transform));
+			style.svg_wrap(format!("<defs><clipPath id=\"{}\"><rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" transform=\"{}\"/></clipPath></defs><g clip-path=\"url(#{})\">{}</g>",
+				id,
+				style.position.x + clip.area[0].x,
+				style.position.y + clip.area[0].y,
+				clip.width(),
+				clip.height(),
+				transform,
+				id,
+				text,
+			))
+		},
+		None => style.svg_wrap(text),
+	}
+}
+
+impl Shape for Text {
+	fn into_svg(&self, style: &Style) -> String {
+		let layout = self.layout().ok();
+		let lines = match &layout {
+			Some(layout) => layout.lines.iter().map(|line| format!("<tspan x=\"{}\" y=\"{}\" word-spacing=\"{}\">{}</tspan>",
+				style.position.x + line.left,
+				style.position.y + line.baseline,
+				self.text_style.word_spacing + line.justify_spacing,
+				escape_svg(&layout.text[line.range.clone()]),
+			)).collect::<String>(),
+			None => escape_svg(&self.text),
+		};
+		let text = format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" {} text-decoration=\"{}\" {} xml:space=\"preserve\">{}</text>", 
+			style.position.x, 
+			style.position.y,
+			layout.as_ref().map_or(self.text_style.resolved_font_size(), |t| t.font_size),
+			self.text_style.svg(),
+			self.text_style.svg_decoration(),
+			style.svg_basic_settings(),
+			lines
+		);
+		svg_text_clip(layout.and_then(|t| t.clip), style, text)
+	}
+
+	fn get_area(&self, style: &Style) -> Area {
+		match self.try_area(style) {
+			Ok(t) => t,
+			Err(_) => Area::new(Vec2::ZERO, Vec2::ZERO).transform(style),
+		}
+	}
+
+	fn delta(&self, _: &Self) -> Self { self.clone() }
+	fn change(&mut self, _: &Self) {}
+	#[cfg(feature = "vertexs")]
+	fn into_vertexs(&self, style: &Style, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
+		let layout = match self.layout() {
+			Ok(t) => t,
+			Err(_) => return (vec!(), vec!(), normalize_clip(style.clip, size)),
+		};
+		let (mut vertexs, mut indices, clip) = glyph_vertexs(&layout.glyphs, layout.clip, |_| None, |_, t| t, style, size);
+		stroke_text_vertexs(&layout_outlines(&layout), layout.clip, style, size, &mut vertexs, &mut indices);
+		decoration_vertexs(&layout, |decoration| decoration.color.map(|t| t.normalized()), |area| vec!(area.points()), style, size, &mut vertexs, &mut indices);
+		(vertexs, indices, clip)
+	}
+}
+
+impl Animate for Text {
+	fn animate(&mut self, _: usize, _: f32) {}
+	fn animate_len(&self) -> usize { 0 }
+}
+
+/// lines drawn with text, refer to css `text-decoration`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default, Copy, Hash, Eq)]
+#[serde(default)]
+pub struct TextDecoration {
+	pub underline: bool,
+	pub overline: bool,
+	pub strikethrough: bool,
+}
+
+impl TextDecoration {
+	/// check if there is no line
+	pub fn is_none(&self) -> bool {
+		!(self.underline || self.overline || self.strikethrough)
+	}
+
+	/// lines in either decoration
+	pub fn union(self, other: Self) -> Self {
+		Self {
+			underline: self.underline || other.underline,
+			overline: self.overline || other.overline,
+			strikethrough: self.strikethrough || other.strikethrough,
+		}
+	}
+
+	/// get svg `text-decoration`
+	pub fn svg(&self) -> String {
+		if self.is_none() {
+			return String::from("none")
+		}
+		let mut back = vec!();
+		if self.underline {
+			back.push("underline");
+		}
+		if self.overline {
+			back.push("overline");
+		}
+		if self.strikethrough {
+			back.push("line-through");
+		}
+		back.join(" ")
+	}
+}
+
+/// a piece of [`RichText`] with its own style
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+pub struct TextSpan {
+	pub text: String,
+	/// font, size and spacings of this span. alignment, wrapping and overflow follow [`RichText::text_style`].
+	pub text_style: TextStyle,
+	/// [`Option::None`] for [`Style::fill`]
+	pub color: Option<Color>,
+	pub decoration: TextDecoration,
+}
+
+impl TextSpan {
+	/// create a span with given text and style
+	pub fn new(text: impl Into<String>, text_style: TextStyle) -> Self {
+		Self {
+			text: text.into(),
+			text_style,
+			..Default::default()
+		}
+	}
+
+	/// set color of this span
+	pub fn color(self, color: impl Into<Color>) -> Self {
+		Self {
+			color: Some(color.into()),
+			..self
+		}
+	}
+
+	/// set decoration of this span
+	pub fn decoration(self, decoration: TextDecoration) -> Self {
+		Self {
+			decoration,
+			..self
+		}
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+/// a text made of spans in different styles, spans are laid out in one flow.
+pub struct RichText {
+	pub spans: Vec<TextSpan>,
+	/// [`Option::None`] stands for the whole screen
+	pub text_width: Option<f32>,
+	/// [`Option::None`] stands for the whole screen
+	pub text_height: Option<f32>,
+	/// alignment, wrapping and overflow of the whole text, also decides height of empty lines.
+	pub text_style: TextStyle,
+}
+
+impl RichText {
+	/// all spans joined together, [`LayoutGlyph::byte_offset`] and [`LayoutLine::range`] refer to this.
+	pub fn text(&self) -> String {
+		self.spans.iter().map(|t| t.text.as_str()).collect()
+	}
+
+	/// where every span is in [`Self::text`]
+	pub fn span_ranges(&self) -> Vec<std::ops::Range<usize>> {
+		let mut start = 0;
+		self.spans.iter().map(|t| {
+			start += t.text.len();
+			start - t.text.len()..start
+		}).collect()
+	}
+
+	/// style of every span, with [`TextSpan::decoration`] joined into [`TextStyle::decoration`]
+	pub fn span_styles(&self) -> Vec<TextStyle> {
+		self.spans.iter().map(|t| TextStyle {
+			decoration: t.text_style.decoration.union(t.decoration),
+			..t.text_style.clone()
+		}).collect()
+	}
+
+	/// place every glyph of this text, returns a error when there is no font to layout with.
+	pub fn layout(&self) -> Result<TextLayout, FontError> {
+		let text_styles = self.span_styles();
+		let spans: Vec<(std::ops::Range<usize>, &TextStyle)> = self.span_ranges().into_iter().zip(text_styles.iter()).collect();
+		layout_spans(&self.text(), &spans, &self.text_style, self.text_width, self.text_height)
+	}
+
+	/// underlines, overlines and strikethroughs of every span, see more in [`Text::decorations`].
+	pub fn decorations(&self) -> Result<Vec<LayoutDecoration>, FontError> {
+		Ok(self.layout()?.decorations)
+	}
+
+	/// outlines of every glyph, see more in [`Text::to_outlines`].
+	pub fn to_outlines(&self) -> Result<Vec<Outline>, FontError> {
+		Ok(layout_outlines(&self.layout()?))
+	}
+
+	/// same as [`Shape::get_area`], but returns a error when there is no font to layout with instead of a empty area.
+	pub fn try_area(&self, style: &Style) -> Result<Area, FontError> {
+		let layout = self.layout()?;
+		Ok(layout.area.transform(style))
+	}
+
+	/// scale font sizes and spacings of every span and the box, see more in [`TextStyle::pre_scale`].
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		for text_style in self.spans.iter_mut().map(|t| &mut t.text_style).chain(std::iter::once(&mut self.text_style)) {
+			text_style.pre_scale(scale_factor);
+		}
+		self.text_width = self.text_width.map(|t| t * scale_factor);
+		self.text_height = self.text_height.map(|t| t * scale_factor);
+	}
+}
+
+impl Shape for RichText {
+	fn into_svg(&self, style: &Style) -> String {
+		let layout = self.layout().ok();
+		let text_styles = self.span_styles();
+		let lines = match &layout {
+			Some(layout) => layout.lines.iter().map(|line| {
+				let glyphs = &layout.glyphs[line.glyphs.clone()];
+				let mut pieces = String::new();
+				// glyphs from the same span goes into one piece
+				let mut start = 0;
+				while start < glyphs.len() {
+					let span_index = glyphs[start].span;
+					let end = glyphs[start..].iter().position(|t| t.span != span_index).map_or(glyphs.len(), |t| t + start);
+					let range = glyphs[start].byte_offset..glyphs.get(end).map_or(line.range.end, |t| t.byte_offset);
+					let span = &self.spans[span_index];
+					let text_style = &text_styles[span_index];
+					let fill = match &span.color {
+						Some(t) => format!(" fill=\"{}\" fill-opacity=\"{}\"", t.svg(), t[3] as f32 / 255.0),
+						None => String::new(),
+					};
+					pieces = format!("{pieces}<tspan font-size=\"{}\" {} word-spacing=\"{}\" text-decoration=\"{}\"{}>{}</tspan>",
+						glyphs[start].font_size,
+						text_style.svg(),
+						text_style.word_spacing + line.justify_spacing,
+						text_style.svg_decoration(),
+						fill,
+						escape_svg(&layout.text[range]),
+					);
+					start = end;
+				}
+				format!("<tspan x=\"{}\" y=\"{}\">{}</tspan>", style.position.x + line.left, style.position.y + line.baseline, pieces)
+			}).collect::<String>(),
+			None => escape_svg(&self.text()),
+		};
+		let text = format!("<text x=\"{}\" y=\"{}\" font-size=\"{}\" {} {} xml:space=\"preserve\">{}</text>", 
+			style.position.x, 
+			style.position.y,
+			layout.as_ref().map_or(self.text_style.resolved_font_size(), |t| t.font_size),
+			self.text_style.svg(),
+			style.svg_basic_settings(),
+			lines
+		);
+		svg_text_clip(layout.and_then(|t| t.clip), style, text)
+	}
+
+	fn get_area(&self, style: &Style) -> Area {
+		match self.try_area(style) {
+			Ok(t) => t,
+			Err(_) => Area::new(Vec2::ZERO, Vec2::ZERO).transform(style),
+		}
+	}
+
+	fn delta(&self, _: &Self) -> Self { self.clone() }
+	fn change(&mut self, _: &Self) {}
+	#[cfg(feature = "vertexs")]
+	fn into_vertexs(&self, style: &Style, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
+		let layout = match self.layout() {
+			Ok(t) => t,
+			Err(_) => return (vec!(), vec!(), normalize_clip(style.clip, size)),
+		};
+		// spans without color use fill paint of style
+		let span_color = |span: usize| self.spans.get(span).and_then(|t| t.color).map(|t| t.normalized());
+		let (mut vertexs, mut indices, clip) = glyph_vertexs(&layout.glyphs, layout.clip, |glyph| span_color(glyph.span), |_, t| t, style, size);
+		stroke_text_vertexs(&layout_outlines(&layout), layout.clip, style, size, &mut vertexs, &mut indices);
+		decoration_vertexs(&layout, |decoration| decoration.color.map(|t| t.normalized()).or_else(|| span_color(decoration.span)), |area| vec!(area.points()), style, size, &mut vertexs, &mut indices);
+		(vertexs, indices, clip)
+	}
+}
+
+impl Animate for RichText {
+	fn animate(&mut self, _: usize, _: f32) {}
+	fn animate_len(&self) -> usize { 0 }
+}
+
+/// the outline a [`TextPath`] follows, in the same coordinate as [`Style::position`] is the origin.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
+pub enum TextPathGuide {
+	/// follows the curve, and then back to start if [`CubicBezier::if_close`]
+	CubicBezier(CubicBezier),
+	/// follows every edge, and then back to start
+	Polygon(Polygon),
+	/// placed like [`Circle`] shape, so the center is at (radius, radius). starts at right and goes clockwise
+	Circle(Circle),
+}
+
+impl Default for TextPathGuide {
+	fn default() -> Self {
+		Self::Circle(Circle::default())
+	}
+}
+
+impl TextPathGuide {
+	/// flatten the guide into connected points
+	pub fn to_points(&self) -> Vec<Vec2> {
+		match self {
+			Self::CubicBezier(t) => {
+				let mut points: Vec<Vec2> = (0..=128).map(|i| t.sample(i as f32 / 128.0)).collect();
+				if t.if_close {
+					points.push(t.points[0]);
+				}
+				points
+			},
+			Self::Polygon(t) => {
+				let mut points = t.points.clone();
+				if let Some(first) = t.points.first() {
+					points.push(*first);
+				}
+				points
+			},
+			Self::Circle(t) => {
+				let segments = ((2.0 * PI * t.radius / 2.0) as usize).clamp(32, 512);
+				(0..=segments).map(|i| t.sample(i as f32 / segments as f32) + Vec2::same(t.radius)).collect()
+			},
+		}
+	}
+
+	/// get `d` of svg `<path>`, moved by `position`
+	pub fn svg(&self, position: Vec2) -> String {
+		match self {
+			Self::CubicBezier(t) => format!("M {} C {} {} {}{}",
+				(t.points[0] + position).svg(),
+				(t.points[1] + position).svg(),
+				(t.points[2] + position).svg(),
+				(t.points[3] + position).svg(),
+				if t.if_close { " Z" } else { "" },
+			),
+			Self::Polygon(t) => {
+				let mut back = String::new();
+				for (i, point) in t.points.iter().enumerate() {
+					let command = if i == 0 { "M" } else { "L" };
+					back = format!("{back}{command} {} ", (*point + position).svg());
+				}
+				format!("{back}Z")
+			},
+			Self::Circle(t) => {
+				let center = position + Vec2::same(t.radius);
+				format!("M {} A {} {} 0 1 1 {} A {} {} 0 1 1 {}",
+					(center + Vec2::x(t.radius)).svg(),
+					t.radius, t.radius,
+					(center - Vec2::x(t.radius)).svg(),
+					t.radius, t.radius,
+					(center + Vec2::x(t.radius)).svg(),
+				)
+			},
+		}
+	}
+}
+
+/// a glyph placed on the path by [`TextPath::glyphs`]
+#[derive(Clone, Debug, PartialEq, Default)]
+pub struct PathGlyph {
+	/// the glyph laid out in a straight line
+	pub glyph: LayoutGlyph,
+	/// where the middle of glyph on baseline is placed on the path, [`Style::position`] is the origin and transform is not applied.
+	pub position: Vec2,
+	/// how much the glyph rotates around `position` in radians, [`TextPath::rotate`] included
+	pub angle: f32,
+	/// how far `position` is from start of the path
+	pub distance: f32,
+	/// the glyph box, from pen position to the next one and from top to bottom of the line, before rotation.
+	pub cell: Area,
+}
+
+impl PathGlyph {
+	/// move a point of the straight laid out glyph onto the path
+	pub fn place(&self, point: Vec2) -> Vec2 {
+		let anchor = Vec2::new(self.glyph.origin.x + self.glyph.advance / 2.0, self.glyph.origin.y);
+		(point - anchor).rotate(self.angle) + self.position
+	}
+
+	/// corners of [`Self::cell`] after placed on the path
+	pub fn corners(&self) -> [Vec2; 4] {
+		[self.place(self.cell.left_top()), self.place(self.cell.right_top()), self.place(self.cell.right_bottom()), self.place(self.cell.left_bottom())]
+	}
+}
+
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[serde(default)]
+/// a single line of text following a outline, refer to svg `<textPath>`.
+///
+/// [`TextStyle::align`] places the text at start, middle or end of the path, [`TextAlign::Justify`] spreads it over the whole path.
+/// glyphs whose middle falls outside the path are hidden.
+pub struct TextPath {
+	pub text: String,
+	pub path: TextPathGuide,
+	/// moves the text along the path in pixels after alignment, refer to svg `startOffset`.
+	pub start_offset: f32,
+	/// extra rotation of every glyph in radians like [`Style::rotate`], refer to svg `rotate` which is in degrees.
+	pub rotate: f32,
+	pub text_style: TextStyle,
+}
+
+impl TextPath {
+	/// how long the path is in pixels
+	pub fn length(&self) -> f32 {
+		self.path.to_points().windows(2).map(|t| (t[1] - t[0]).len()).sum()
+	}
+
+	/// find the point `distance` pixels away from start of the path and the direction there in radians, [`Option::None`] if outside the path.
+	pub fn point_at(&self, distance: f32) -> Option<(Vec2, f32)> {
+		point_on(&self.path.to_points(), distance)
+	}
+
+	/// place every glyph onto the path, returns a error when there is no font to layout with.
+	pub fn glyphs(&self) -> Result<Vec<PathGlyph>, FontError> {
+		Ok(self.placed()?.1)
+	}
+
+	/// the straight layout and glyphs bent from it
+	fn placed(&self) -> Result<(TextLayout, Vec<PathGlyph>), FontError> {
+		// lay out as a single line first, and then bend it
+		let text = self.text.replace('\n', " ");
+		let text_style = TextStyle {
+			align: TextAlign::Left,
+			wrap: TextWrap::None,
+			overflow: TextOverflow::Visible,
+			..self.text_style.clone()
+		};
+		let layout = layout_spans(&text, &[(0..text.len(), &text_style)], &text_style, None, None)?;
+		let points = self.path.to_points();
+		let length: f32 = points.windows(2).map(|t| (t[1] - t[0]).len()).sum();
+		let width = layout.glyphs.last().map_or(0.0, |t| t.origin.x + t.advance);
+		let (start, spacing) = match self.text_style.align {
+			TextAlign::Left => (0.0, 0.0),
+			TextAlign::Center => ((length - width) / 2.0, 0.0),
+			TextAlign::Right => (length - width, 0.0),
+			TextAlign::Justify => (0.0, (length - self.start_offset - width) / (layout.glyphs.len().max(2) - 1) as f32),
+		};
+		let mut back = vec!();
+		for (i, glyph) in layout.glyphs.iter().enumerate() {
+			let distance = self.start_offset + start + spacing * i as f32 + glyph.origin.x + glyph.advance / 2.0;
+			let (position, angle) = match point_on(&points, distance) {
+				Some(t) => t,
+				None => continue,
+			};
+			let line = &layout.lines[glyph.line];
+			back.push(PathGlyph {
+				cell: Area::new(Vec2::new(glyph.origin.x, line.top), Vec2::new(glyph.origin.x + glyph.advance, line.top + line.height)),
+				glyph: glyph.clone(),
+				position,
+				angle: angle + self.rotate,
+				distance,
+			});
+		}
+		Ok((layout, back))
+	}
+
+	/// outlines of every glyph placed on the path, with [`Style::position`] as origin, transform is not applied.
+	pub fn to_outlines(&self) -> Result<Vec<Outline>, FontError> {
+		let glyphs = self.glyphs()?;
+		let laid_out: Vec<LayoutGlyph> = glyphs.iter().map(|t| t.glyph.clone()).collect();
+		Ok(glyph_outlines(&laid_out, |index, point| glyphs[index].place(point)))
+	}
+
+	/// same as [`Self::to_outlines`], but flattened into polygons, see more in [`Text::to_polygons`].
+	pub fn to_polygons(&self, tolerance: f32) -> Result<Vec<Polygon>, FontError> {
+		Ok(self.to_outlines()?.iter().flat_map(|t| t.to_polygons(tolerance)).collect())
+	}
+
+	/// scale the path, offset and everything in [`TextPath::text_style`], see more in [`TextStyle::pre_scale`].
+	pub fn pre_scale(&mut self, scale_factor: f32) {
+		self.text_style.pre_scale(scale_factor);
+		self.start_offset *= scale_factor;
+		match &mut self.path {
+			TextPathGuide::CubicBezier(t) => {
+				for point in &mut t.points {
+					*point = *point * scale_factor;
+				}
+			},
+			TextPathGuide::Polygon(t) => {
+				for point in &mut t.points {
+					*point = *point * scale_factor;
+				}
+			},
+			TextPathGuide::Circle(t) => t.radius *= scale_factor,
+		}
+	}
+
+	/// same as [`Shape::get_area`], but returns a error when there is no font to layout with instead of a empty area.
+	pub fn try_area(&self, style: &Style) -> Result<Area, FontError> {
+		let mut area: Option<Area> = None;
+		for glyph in self.glyphs()? {
+			for corner in glyph.corners() {
+				let corner = Area::new(corner, corner);
+				match &mut area {
+					Some(t) => {
+						t.area[0] = Vec2::new(t.area[0].x.min(corner.area[0].x), t.area[0].y.min(corner.area[0].y));
+						t.area[1] = Vec2::new(t.area[1].x.max(corner.area[1].x), t.area[1].y.max(corner.area[1].y));
+					},
+					None => area = Some(corner),
+				}
+			}
+		}
+		Ok(area.unwrap_or(Area::ZERO).transform(style))
+	}
+}
+
+/// find the point `distance` away from start of connected `points` and the direction there in radians
+fn point_on(points: &[Vec2], distance: f32) -> Option<(Vec2, f32)> {
+	if distance < 0.0 {
+		return None
+	}
+	let mut walked = 0.0;
+	for segment in points.windows(2) {
+		let delta = segment[1] - segment[0];
+		let length = delta.len();
+		if length <= 0.0 {
+			continue;
+		}
+		if walked + length >= distance {
+			let t = (distance - walked) / length;
+			return Some((segment[0] + delta * t, delta.y.atan2(delta.x)))
 		}
+		walked += length;
 	}
+	None
 }
 
-impl Shape for Text {
+impl Shape for TextPath {
 	fn into_svg(&self, style: &Style) -> String {
-		format!("<text x=\"{}\" y=\"{}\" {}>{}</text>", 
-			style.position.x, 
-			style.position.y,
+		let d = self.path.svg(style.position);
+		let id = svg_id("nablo_text_path", &d);
+		let length = self.length();
+		let (offset, anchor, spread) = match self.text_style.align {
+			TextAlign::Left => (self.start_offset, "start", String::new()),
+			TextAlign::Center => (self.start_offset + length / 2.0, "middle", String::new()),
+			TextAlign::Right => (self.start_offset + length, "end", String::new()),
+			TextAlign::Justify => (self.start_offset, "start", format!(" textLength=\"{}\" lengthAdjust=\"spacing\"", length - self.start_offset)),
+		};
+		style.svg_wrap(format!("<defs><path id=\"{}\" d=\"{}\"/></defs><text font-size=\"{}\" {} {} rotate=\"{}\" xml:space=\"preserve\"><textPath href=\"#{}\" startOffset=\"{}\" text-anchor=\"{}\" word-spacing=\"{}\"{}>{}</textPath></text>",
+			id,
+			d,
+			self.text_style.resolved_font_size(),
+			self.text_style.svg(),
 			style.svg_basic_settings(),
-			self.text
-			)
+			self.rotate / PI * 180.0,
+			id,
+			offset,
+			anchor,
+			self.text_style.word_spacing,
+			spread,
+			escape_svg(&self.text.replace('\n', " ")),
+		))
 	}
 
 	fn get_area(&self, style: &Style) -> Area {
-		// since we haven't provid font change functions, this would not associated with font.
-		let em = EM * style.size.len() / 2.0_f32.sqrt() * CORRECTION;
-		let mut x = vec!(0.0);
-		let mut line = 0.0;
-		let fonts = &[FONT.clone()];
-		for inner in self.text.split("\n") {
-			let mut layout = fontdue::layout::Layout::new(fontdue::layout::CoordinateSystem::PositiveYDown);
-			layout.reset(&Default::default());
-			layout.append(fonts, &fontdue::layout::TextStyle::new(inner, em, 0));
-			let glyphs = layout.glyphs();
-			if glyphs.len() > 0 {
-				x[line as usize] = x[line as usize] + glyphs[glyphs.len() - 1].x + glyphs[glyphs.len() - 1].width as f32 - 0.25;
-			}
-			x.push(0.0);
-			line = line + 1.0;
-		};
-		Area::new(Vec2::ZERO, Vec2::new(*x.iter().max_by(|a, b| a.partial_cmp(b).unwrap()).unwrap(), em * line)).transform(style)
+		match self.try_area(style) {
+			Ok(t) => t,
+			Err(_) => Area::new(Vec2::ZERO, Vec2::ZERO).transform(style),
+		}
 	}
 
 	fn delta(&self, _: &Self) -> Self { self.clone() }
 	fn change(&mut self, _: &Self) {}
 	#[cfg(feature = "vertexs")]
-	fn into_vertexs(&self, _: &Style, _: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) { todo!() }
+	fn into_vertexs(&self, style: &Style, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
+		let (layout, glyphs) = match self.placed() {
+			Ok(t) => t,
+			Err(_) => return (vec!(), vec!(), normalize_clip(style.clip, size)),
+		};
+		let laid_out: Vec<LayoutGlyph> = glyphs.iter().map(|t| t.glyph.clone()).collect();
+		let place = |index: usize, point: Vec2| glyphs[index].place(point);
+		let (mut vertexs, mut indices, clip) = glyph_vertexs(&laid_out, None, |_| None, place, style, size);
+		stroke_text_vertexs(&glyph_outlines(&laid_out, place), None, style, size, &mut vertexs, &mut indices);
+		// a decoration is cut at every glyph, so it bends with the path
+		decoration_vertexs(&layout, |decoration| decoration.color.map(|t| t.normalized()), |area| glyphs.iter().filter_map(|glyph| {
+			let (left, right) = (area.area[0].x.max(glyph.cell.area[0].x), area.area[1].x.min(glyph.cell.area[1].x));
+			if left >= right {
+				return None
+			}
+			Some(Area::new(Vec2::new(left, area.area[0].y), Vec2::new(right, area.area[1].y)).points().into_iter().map(|point| glyph.place(point)).collect())
+		}).collect(), style, size, &mut vertexs, &mut indices);
+		(vertexs, indices, clip)
+	}
 }
 
-impl Animate for Text {
-	fn animate(&mut self, _: usize, _: f32) {}
-	fn animate_len(&self) -> usize { 0 }
+/// 0 for [`TextPath::start_offset`], 1 for [`TextPath::rotate`]
+impl Animate for TextPath {
+	fn animate(&mut self, id: usize, change_to: f32) {
+		match id {
+			0 => self.start_offset = change_to,
+			1 => self.rotate = change_to,
+			_ => {},
+		}
+	}
+
+	fn animate_len(&self) -> usize { 2 }
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
@@ -755,14 +3640,14 @@ impl Shape for CubicBezier {
 		}else {
 			z = String::new()
 		}
-		format!("<path d=\"M {} C {} {} {}\" {} {}/>", 
+		style.svg_wrap(format!("<path d=\"M {} C {} {} {}\" {} {}/>", 
 			(style.position + self.points[0]).svg(),
 			(style.position + self.points[1]).svg(),
 			(style.position + self.points[2]).svg(), 
 			(style.position + self.points[3]).svg(),
 			style.svg_basic_settings(),
 			z
-			)
+			))
 	}
 
 	fn get_area(&self, style: &Style) -> Area {
@@ -925,7 +3810,7 @@ impl Shape for Svg {
 impl Animate for Svg {}
 
 /// a image
-#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default)]
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq)]
 #[serde(default)]
 pub struct Image {
 	/// image id
@@ -933,19 +3818,398 @@ pub struct Image {
 	/// image size
 	pub size: Vec2,
 	/// image mask, only inside will show
-	pub mask: Option<ShapeMask>
+	pub mask: Option<ShapeMask>,
+	/// the part of image to draw, useful for sprite sheets or atlases. in pixels of `intrinsic_size`, or in [0..=1] if `intrinsic_size` is unknown.
+	/// [`Option::None`] for the whole image.
+	pub source: Option<Area>,
+	/// how large the image actually is in pixels, [`Option::None`] for unknown. required by `fit` except [`ImageFit::Fill`].
+	pub intrinsic_size: Option<Vec2>,
+	/// how to put source into `size`
+	pub fit: ImageFit,
+	/// multiplied into every pixel of the image
+	pub tint: Color,
+	/// draw as a stretchable skin, `fit` will be ignored if is not [`Option::None`].
+	pub nine_slice: Option<NineSlice>,
+	/// treat `source` as a grid of frames and only draw one of them.
+	pub sprite: Option<SpriteSheet>,
 }
 
-impl Shape for Image {	
-	fn into_svg(&self, _: &Style) -> std::string::String { todo!() }
-	fn get_area(&self, style: &Style) -> Area { [style.position, style.position+self.size].into() }
-	fn delta(&self, _: &Self) -> Self { todo!() }
-	fn change(&mut self, _: &Self) { todo!() }
+/// a grid-based sprite sheet, frames are counted from left to right and then top to bottom.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+#[serde(default)]
+pub struct SpriteSheet {
+	pub columns: usize,
+	pub rows: usize,
+	/// current frame, wraps around `frame_count`
+	pub frame: usize,
+	/// how many frames are used, 0 for the whole grid
+	pub frame_count: usize,
+}
+
+impl SpriteSheet {
+	/// create a sprite sheet using the whole grid
+	pub fn new(columns: usize, rows: usize) -> Self {
+		Self {
+			columns,
+			rows,
+			..Default::default()
+		}
+	}
+
+	/// how many frames are there
+	pub fn len(&self) -> usize {
+		let cells = self.columns * self.rows;
+		if self.frame_count == 0 {
+			cells
+		}else {
+			self.frame_count.min(cells)
+		}
+	}
+
+	/// check if there is no frame
+	pub fn is_empty(&self) -> bool {
+		self.len() == 0
+	}
+
+	/// get the area of current frame inside `sheet`
+	pub fn frame_area(&self, sheet: Area) -> Area {
+		if self.is_empty() {
+			return sheet
+		}
+		let frame = self.frame % self.len();
+		let cell = sheet.width_and_height() / Vec2::new(self.columns as f32, self.rows as f32);
+		let left_top = sheet.area[0] + Vec2::new((frame % self.columns) as f32, (frame / self.columns) as f32) * cell;
+		Area::new(left_top, left_top + cell)
+	}
+}
+
+/// insets of a nine-slice (also called nine-patch) image, corners keep unscaled and edges and center are stretched.
+///
+/// in the same unit as [`Image::source`].
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Default, Copy)]
+#[serde(default)]
+pub struct NineSlice {
+	pub left: f32,
+	pub top: f32,
+	pub right: f32,
+	pub bottom: f32,
+}
+
+impl Default for Image {
+	fn default() -> Self {
+		Self {
+			id: String::new(),
+			size: Vec2::ZERO,
+			mask: None,
+			source: None,
+			intrinsic_size: None,
+			fit: ImageFit::default(),
+			tint: Color::WHITE,
+			nine_slice: None,
+			sprite: None,
+		}
+	}
+}
+
+/// how to put a image into its size, refer to css `object-fit`.
+#[derive(serde::Deserialize, serde::Serialize, Clone, Debug, PartialEq, Eq, Default, Copy, Hash)]
+pub enum ImageFit {
+	/// stretch to fill the whole size
+	#[default] Fill,
+	/// keep aspect ratio and show the whole image, may leave empty place
+	Contain,
+	/// keep aspect ratio and fill the whole size, may cut the image
+	Cover,
+	/// keep original size and put in center, may cut the image
+	None,
+}
+
+impl ImageFit {
+	/// get fit mode from id, used in animation.
+	pub fn from_id(id: usize) -> Self {
+		match id {
+			0 => Self::Fill,
+			1 => Self::Contain,
+			2 => Self::Cover,
+			_ => Self::None,
+		}
+	}
+
+	/// get id of fit mode, used in animation.
+	pub fn into_id(&self) -> usize {
+		match self {
+			Self::Fill => 0,
+			Self::Contain => 1,
+			Self::Cover => 2,
+			Self::None => 3,
+		}
+	}
+}
+
+impl Image {
+	/// animation id of sprite frame, used by [`StyleToAnimate::Id`](crate::shape::animation::StyleToAnimate::Id) directly.
+	/// ids of [`Animate`] for [`Shape`](crate::shape::Shape) start after fixed ids of style, so use [`Style::FIXED_LEN`] + this there.
+	pub const FRAME_ID: usize = 15;
+
+	/// the source area in pixels of `intrinsic_size`, or in [0..=1] if `intrinsic_size` is unknown.
+	///
+	/// only current frame is included when `sprite` is not [`Option::None`].
+	pub fn source_area(&self) -> Area {
+		match &self.sprite {
+			Some(sprite) => sprite.frame_area(self.sheet_area()),
+			None => self.sheet_area(),
+		}
+	}
+
+	/// the source area ignoring `sprite`
+	fn sheet_area(&self) -> Area {
+		match self.source {
+			Some(t) => t,
+			None => Area::new_with_origin(self.intrinsic_size.unwrap_or(Vec2::NOT_TO_SCALE)),
+		}
+	}
+
+	/// caculate where to draw and which part of texture to use.
+	///
+	/// returns destination area relative to [`Style::position`] and texture coordinate area in [0..=1].
+	pub fn layout(&self) -> (Area, Area) {
+		let intrinsic_size = self.intrinsic_size.unwrap_or(Vec2::NOT_TO_SCALE);
+		let source = self.source_area();
+		let content = source.width_and_height();
+		let scale = match (self.fit, self.intrinsic_size) {
+			(ImageFit::Fill, _) | (_, None) => self.size / content,
+			(ImageFit::Contain, _) => Vec2::same((self.size / content).x.min((self.size / content).y)),
+			(ImageFit::Cover, _) => Vec2::same((self.size / content).x.max((self.size / content).y)),
+			(ImageFit::None, _) => Vec2::NOT_TO_SCALE,
+		};
+		let content_size = content * scale;
+		let offset = (self.size - content_size) / 2.0;
+		let destination = Area::new(offset, offset + content_size).cross_part(&Area::new_with_origin(self.size));
+		let uv = Area::new(
+			(source.area[0] + (destination.area[0] - offset) / scale) / intrinsic_size,
+			(source.area[0] + (destination.area[1] - offset) / scale) / intrinsic_size,
+		);
+		(destination, uv)
+	}
+
+	/// caculate every part of the image should be drawn, returns destination areas relative to [`Style::position`] and texture coordinate areas in [0..=1].
+	///
+	/// gives 9 parts for nine-slice images (empty parts are skipped), otherwise the same as [`Self::layout`].
+	pub fn patches(&self) -> Vec<(Area, Area)> {
+		let slice = match self.nine_slice {
+			Some(t) => t,
+			None => return vec!(self.layout()),
+		};
+		let intrinsic_size = self.intrinsic_size.unwrap_or(Vec2::NOT_TO_SCALE);
+		let source = self.source_area();
+		// corners would be shrinked when there is no enough space
+		let shrink_x = (self.size.x / (slice.left + slice.right)).min(1.0);
+		let shrink_y = (self.size.y / (slice.top + slice.bottom)).min(1.0);
+		let destination_x = [0.0, slice.left * shrink_x, self.size.x - slice.right * shrink_x, self.size.x];
+		let destination_y = [0.0, slice.top * shrink_y, self.size.y - slice.bottom * shrink_y, self.size.y];
+		let source_x = [source.area[0].x, source.area[0].x + slice.left, source.area[1].x - slice.right, source.area[1].x];
+		let source_y = [source.area[0].y, source.area[0].y + slice.top, source.area[1].y - slice.bottom, source.area[1].y];
+		let mut back = vec!();
+		for y in 0..3 {
+			for x in 0..3 {
+				let destination = Area::new(Vec2::new(destination_x[x], destination_y[y]), Vec2::new(destination_x[x + 1], destination_y[y + 1]));
+				if destination.is_empty() {
+					continue;
+				}
+				let uv = Area::new(Vec2::new(source_x[x], source_y[y]) / intrinsic_size, Vec2::new(source_x[x + 1], source_y[y + 1]) / intrinsic_size);
+				back.push((destination, uv));
+			}
+		}
+		back
+	}
+
+	/// get svg `<filter>` and the `filter` attribute to apply tint, both empty if tint is white.
+	fn svg_tint(&self) -> (String, String) {
+		if self.tint == Color::WHITE {
+			return (String::new(), String::new())
+		}
+		let id = svg_id("nablo_tint", &format!("{:?}", self.tint));
+		// vertexs multiply tint in srgb, so does the filter
+		(format!("<filter id=\"{}\" color-interpolation-filters=\"sRGB\"><feColorMatrix type=\"matrix\" values=\"{} 0 0 0 0 0 {} 0 0 0 0 0 {} 0 0 0 0 0 {} 0\"/></filter>",
+			id,
+			self.tint[0] as f32 / 255.0,
+			self.tint[1] as f32 / 255.0,
+			self.tint[2] as f32 / 255.0,
+			self.tint[3] as f32 / 255.0,
+		), format!(" filter=\"url(#{})\"", id))
+	}
+}
+
+impl Shape for Image {
+	fn into_svg(&self, style: &Style) -> std::string::String {
+		let (mask_def, mask_clip) = match &self.mask {
+			Some(mask) => {
+				let mask = ClipMask {
+					mask: mask.clone(),
+					position: style.position,
+				};
+				let id = svg_id("nablo_image_mask", &format!("{:?}", mask));
+				(format!("<clipPath id=\"{}\">{}</clipPath>", id, mask.svg()), format!(" clip-path=\"url(#{})\"", id))
+			},
+			None => (String::new(), String::new())
+		};
+		let (tint_def, tint_filter) = self.svg_tint();
+		let intrinsic_size = self.intrinsic_size.unwrap_or(Vec2::NOT_TO_SCALE);
+		let mut images = String::new();
+		for (destination, uv) in self.patches() {
+			let view_box = Area::new(uv.area[0] * intrinsic_size, uv.area[1] * intrinsic_size);
+			images = format!("{images}<svg x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" viewBox=\"{} {} {} {}\" preserveAspectRatio=\"none\" overflow=\"hidden\"><image href=\"{}\" width=\"{}\" height=\"{}\" preserveAspectRatio=\"none\"/></svg>",
+				style.position.x + destination.area[0].x,
+				style.position.y + destination.area[0].y,
+				destination.width(),
+				destination.height(),
+				view_box.area[0].x,
+				view_box.area[0].y,
+				view_box.width(),
+				view_box.height(),
+				self.id,
+				intrinsic_size.x,
+				intrinsic_size.y,
+			);
+		}
+		style.svg_wrap(format!("<defs>{}{}</defs><g {}><g{}{}>{}</g></g>",
+			mask_def,
+			tint_def,
+			style.svg_basic_settings(),
+			mask_clip,
+			tint_filter,
+			images,
+		))
+	}
+
+	fn get_area(&self, style: &Style) -> Area {
+		Area::new(Vec2::ZERO, self.size).transform(style)
+	}
+
+	/// `size` and `source` are diffed as numbers, `source` only when both images have one.
+	/// `tint`, `fit` and `nine_slice` are carried by delta as they are, adding colors saturates and the other two are not numbers.
+	/// `id`, `mask`, `intrinsic_size` and `sprite` belong to every image itself, so they are never changed.
+	fn delta(&self, rhs: &Self) -> Self {
+		Self {
+			size: self.size - rhs.size,
+			source: match (self.source, rhs.source) {
+				(Some(a), Some(b)) => Some(Area { area: [a.area[0] - b.area[0], a.area[1] - b.area[1]] }),
+				(source, _) => source,
+			},
+			..self.clone()
+		}
+	}
+
+	fn change(&mut self, rhs: &Self) {
+		self.size = self.size + rhs.size;
+		if let (Some(source), Some(delta)) = (&mut self.source, rhs.source) {
+			*source = Area { area: [source.area[0] + delta.area[0], source.area[1] + delta.area[1]] };
+		}
+		self.tint = rhs.tint;
+		self.fit = rhs.fit;
+		self.nine_slice = rhs.nine_slice;
+	}
+
 	#[cfg(feature = "vertexs")]
-	fn into_vertexs(&self, _: &Style, _: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) { todo!() }
+	fn into_vertexs(&self, style: &Style, size: Vec2) -> (Vec<Vertex>, Vec<u32>, Area) {
+		let transform = style_transform(style);
+		let inverse = transform.inverse();
+		let normalize = |point: Point| Vec2::new(point.x / size.x * 2.0 - 1.0, - (point.y / size.y * 2.0 - 1.0));
+		let clip = normalize_clip(style.clip, size);
+		let color = self.tint.normalized();
+		let mask = match &self.mask {
+			Some(t) => {
+				let mask_style = Style {
+					fill: Color::WHITE,
+					fill_paint: None,
+					stroke_width: 0.0,
+					clip_masks: vec!(),
+					..style.clone()
+				};
+				let (vertexs, indices, _) = t.into_vertexs(size, &mask_style);
+				Some((vertexs, indices))
+			},
+			None => None,
+		};
+		let mut vertexs = vec!();
+		let mut indices = vec!();
+		for (destination, uv) in self.patches() {
+			let to_uv = |local: Vec2| {
+				let t = (local - destination.area[0]) / destination.width_and_height();
+				uv.area[0] + t * uv.width_and_height()
+			};
+			let corners = [destination.left_top(), destination.right_top(), destination.right_bottom(), destination.left_bottom()];
+			let quad: Vec<Vec2> = corners.iter().map(|point| normalize(transform.transform_point(point.to_point()))).collect();
+			let offset = vertexs.len() as u32;
+			match &mask {
+				None => {
+					vertexs.extend(quad.iter().zip(corners.iter()).map(|(position, local)| Vertex {
+						position: [position.x, position.y, 0.0],
+						color,
+						uv: [to_uv(*local).x, to_uv(*local).y],
+					}));
+					indices.extend([offset, offset + 1, offset + 2, offset, offset + 2, offset + 3]);
+				},
+				Some((mask_vertexs, mask_indices)) => {
+					// only inside the image can be textured
+					let (mut patch_vertexs, patch_indices) = clip_vertexs(mask_vertexs, mask_indices, &quad);
+					for vertex in &mut patch_vertexs {
+						let position = point((vertex.position[0] + 1.0) / 2.0 * size.x, (1.0 - vertex.position[1]) / 2.0 * size.y);
+						let local = match &inverse {
+							Some(t) => t.transform_point(position),
+							None => position,
+						};
+						let uv = to_uv(Vec2::new(local.x, local.y));
+						vertex.uv = [uv.x, uv.y];
+						vertex.color = color;
+					}
+					vertexs.append(&mut patch_vertexs);
+					indices.extend(patch_indices.into_iter().map(|t| t + offset));
+				},
+			}
+		}
+		(vertexs, indices, clip)
+	}
 }
 
-impl Animate for Image {}
+/// id = 0 or 1 represents size.x or size.y. id = 2, 3, 4 or 5 represents min x, min y, max x or max y of source.
+/// id = 6, 7, 8 or 9 represents rgba of tint. id = 10 represents fit, see more in [`ImageFit::from_id`].
+/// id = 11, 12, 13 or 14 represents left, top, right or bottom of nine slice, only works when `nine_slice` is not [`Option::None`].
+/// id = 15 ([`Image::FRAME_ID`]) represents frame of sprite sheet (rounded down), only works when `sprite` is not [`Option::None`], use [`crate::shape::animation::AnimationLinker::Steps`] to animate it.
+impl Animate for Image {
+	fn animate(&mut self, id: usize, change_to: f32) {
+		match id {
+			0 => self.size.x = change_to,
+			1 => self.size.y = change_to,
+			2..=5 => {
+				let mut source = self.sheet_area();
+				match id {
+					2 => source.area[0].x = change_to,
+					3 => source.area[0].y = change_to,
+					4 => source.area[1].x = change_to,
+					_ => source.area[1].y = change_to,
+				}
+				self.source = Some(source);
+			},
+			6..=9 => self.tint[id - 6] = (compress(change_to) * 255.0) as u8,
+			10 => self.fit = ImageFit::from_id(change_to.max(0.0).round() as usize),
+			11..=14 => if let Some(slice) = &mut self.nine_slice {
+				match id {
+					11 => slice.left = change_to,
+					12 => slice.top = change_to,
+					13 => slice.right = change_to,
+					_ => slice.bottom = change_to,
+				}
+			},
+			Self::FRAME_ID => if let Some(sprite) = &mut self.sprite {
+				sprite.frame = change_to.max(0.0).floor() as usize;
+			},
+			_ => {},
+		}
+	}
+
+	fn animate_len(&self) -> usize { 16 }
+}
 
 /// useful for drawing gradient color or other complex shapes. should be sorted by counterclockwise.
 ///
@@ -1008,10 +4272,10 @@ impl Shape for Polygon {
 			points = format!("{points} {}", (*point + style.position).svg())
 		}
 
-		format!("<polygon  points=\"{}\" {} />", 
+		style.svg_wrap(format!("<polygon  points=\"{}\" {} />", 
 			points,
 			style.svg_basic_settings()
-			) 
+			))
 	}
 	fn get_area(&self, style: &Style) -> Area {
 		// stupid
@@ -1110,6 +4374,27 @@ impl Polygon {
 		result
 	}
 
+	/// check if this polygon is convex, points can be sorted by either clockwise or counterclockwise.
+	pub fn is_convex(&self) -> bool {
+		let len = self.points.len();
+		if len < 3 {
+			return false
+		}
+		let mut sign = 0.0;
+		for i in 0..len {
+			let cross = (self[(i + 1) % len] - self[i]).cross(self[(i + 2) % len] - self[(i + 1) % len]);
+			if cross == 0.0 {
+				continue;
+			}
+			if sign == 0.0 {
+				sign = cross.signum();
+			}else if sign != cross.signum() {
+				return false
+			}
+		}
+		sign != 0.0
+	}
+
 	/// move a polygon to a new place
 	pub fn move_to(&mut self, position: Vec2) {
 		for point in &mut self.points {
@@ -1156,4 +4441,513 @@ fn compress(input: f32) -> f32 {
 		return 1.0;
 	}
 	input
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn red_to_blue() -> Paint {
+		Paint::Linear(LinearGradient {
+			start: Vec2::ZERO,
+			end: Vec2::new(100.0, 0.0),
+			stops: vec!(GradientStop { offset: 0.0, color: [255, 0, 0, 255].into() }, GradientStop { offset: 1.0, color: [0, 0, 255, 255].into() }),
+			..Default::default()
+		})
+	}
+
+	#[test]
+	fn linear_gradient_interpolates_between_stops() {
+		let paint = red_to_blue();
+		assert_eq!(paint.color_at(Vec2::ZERO), [255, 0, 0, 255].into());
+		assert_eq!(paint.color_at(Vec2::new(100.0, 30.0)), [0, 0, 255, 255].into());
+		let middle = paint.color_at(Vec2::new(50.0, -30.0));
+		assert!(middle[0] > 0 && middle[0] < 255 && middle[0] == middle[2] && middle[1] == 0, "{middle:?}");
+		// padded outside
+		assert_eq!(paint.color_at(Vec2::new(-50.0, 0.0)), [255, 0, 0, 255].into());
+		assert_eq!(paint.ramp(3)[0], [255, 0, 0, 255].into());
+		assert_eq!(paint.ramp(3)[2], [0, 0, 255, 255].into());
+	}
+
+	#[test]
+	fn spread_modes() {
+		assert_eq!(SpreadMode::Pad.apply(1.25), 1.0);
+		assert_eq!(SpreadMode::Pad.apply(-0.25), 0.0);
+		assert_eq!(SpreadMode::Repeat.apply(1.25), 0.25);
+		assert_eq!(SpreadMode::Repeat.apply(-0.25), 0.75);
+		assert_eq!(SpreadMode::Reflect.apply(1.25), 0.75);
+		assert_eq!(SpreadMode::Reflect.apply(-0.25), 0.25);
+	}
+
+	#[test]
+	fn radial_and_conic_offsets() {
+		let radial = Paint::Radial(RadialGradient { center: Vec2::same(10.0), radius: 20.0, ..Default::default() });
+		assert_eq!(radial.offset_at(Vec2::new(20.0, 10.0)), 0.5);
+		assert_eq!(radial.offset_at(Vec2::new(10.0, 40.0)), 1.5);
+		let conic = Paint::Conic(ConicGradient { angle: PI / 2.0, ..Default::default() });
+		// clockwise on screen, starts from straight down
+		assert!((conic.offset_at(Vec2::new(-10.0, 10.0)) - 0.125).abs() < 1e-5);
+		assert!((conic.offset_at(Vec2::new(-10.0, -10.0)) - 0.375).abs() < 1e-5);
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn clip_vertexs_cuts_triangles() {
+		let vertex = |x: f32, y: f32| Vertex { position: [x, y, 0.0], color: [1.0; 4], uv: [x, y] };
+		let quad = [vertex(-1.0, -1.0), vertex(1.0, -1.0), vertex(1.0, 1.0), vertex(-1.0, 1.0)];
+		let square = [Vec2::same(-0.5), Vec2::new(0.5, -0.5), Vec2::same(0.5), Vec2::new(-0.5, 0.5)];
+		let (vertexs, indices) = clip_vertexs(&quad, &[0, 1, 2, 0, 2, 3], &square);
+		let point = |i: u32| Vec2::new(vertexs[i as usize].position[0], vertexs[i as usize].position[1]);
+		let area: f32 = indices.chunks_exact(3).map(|t| (point(t[1]) - point(t[0])).cross(point(t[2]) - point(t[0])).abs() / 2.0).sum();
+		assert!((area - 1.0).abs() < 1e-4, "{area}");
+		// uv is cut together with position
+		assert!(vertexs.iter().all(|t| t.position[0].abs() <= 0.5 + 1e-5 && t.position[1].abs() <= 0.5 + 1e-5 && (t.uv[0] - t.position[0]).abs() < 1e-5));
+		let reversed: Vec<Vec2> = square.iter().rev().copied().collect();
+		assert_eq!(clip_vertexs(&quad, &[0, 1, 2, 0, 2, 3], &reversed).1.len(), indices.len());
+	}
+
+	#[test]
+	#[cfg(feature = "vertexs")]
+	fn convex_masks_clip_on_cpu_and_others_use_stencil() {
+		let size = Vec2::same(100.0);
+		let mut style = Style {
+			clip_masks: vec!(ClipMask { mask: ShapeMask::Rect(Rect { width_and_height: Vec2::same(50.0), ..Default::default() }), position: Vec2::ZERO }),
+			..Default::default()
+		};
+		let (vertexs, indices, _) = Rect { width_and_height: Vec2::same(100.0), ..Default::default() }.into_vertexs(&style, size);
+		let (vertexs, _) = style.cpu_clip(vertexs, indices, size);
+		// the left top quarter, y goes up after normalized
+		assert!(!vertexs.is_empty() && vertexs.iter().all(|t| t.position[0] <= 1e-4 && t.position[1] >= -1e-4));
+		assert!(style.stencil_masks(size).is_empty());
+		style.clip_masks.push(ClipMask { mask: ShapeMask::Polygon(vec!(Vec2::ZERO, Vec2::new(50.0, 0.0), Vec2::same(10.0), Vec2::new(0.0, 50.0)).into()), position: Vec2::ZERO });
+		assert_eq!(style.stencil_masks(size).len(), 1);
+	}
+
+	#[test]
+	#[cfg(not(feature = "default-font"))]
+	fn text_without_fonts_reports_no_font() {
+		let text = Text { text: String::from("a"), ..Default::default() };
+		assert_eq!(text.layout().unwrap_err(), FontError::NoFont);
+		// still exported and measured without panicking
+		assert!(text.into_svg(&Style::default()).contains("a</text>"));
+		assert_eq!(text.get_area(&Style::default()), Area::ZERO);
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn default_font_is_loaded() {
+		let registry = FontRegistry::global();
+		assert_eq!(registry.default_family(), Some(crate::shape::registry::DEFAULT_FAMILY));
+		assert!(registry.faces()[0].data.is_some());
+	}
+
+	#[test]
+	fn font_size_and_line_height() {
+		let text_style = TextStyle::default();
+		assert_eq!((text_style.resolved_font_size(), text_style.resolved_line_height()), (EM * CORRECTION, EM * CORRECTION));
+		let text_style = text_style.set_font_size(20.0).set_line_height(1.5);
+		assert_eq!(text_style.resolved_line_height(), 30.0);
+		let svg = Text { text: String::from("a"), text_style, ..Default::default() }.into_svg(&Style::default());
+		assert!(svg.contains("font-size=\"20\""), "{svg}");
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn lines_are_line_height_apart() {
+		let text = Text {
+			text: String::from("a\nb"),
+			text_style: TextStyle::default().set_font_size(20.0).set_line_height(2.0),
+			..Default::default()
+		};
+		let layout = text.layout().unwrap();
+		assert_eq!((layout.font_size, layout.line_height), (20.0, 40.0));
+		assert_eq!(layout.lines.len(), 2);
+		assert_eq!(layout.lines[1].top - layout.lines[0].top, 40.0);
+		assert!(layout.glyphs.iter().all(|t| t.font_size == 20.0));
+	}
+
+	#[cfg(feature = "default-font")]
+	fn layout_of(text: &str, text_style: TextStyle, text_width: Option<f32>, text_height: Option<f32>) -> TextLayout {
+		Text { text: String::from(text), text_style, text_width, text_height }.layout().unwrap()
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn wrap_modes() {
+		let word = layout_of("mmm", TextStyle::default(), None, None).lines[0].width;
+		let width = Some(word * 1.5);
+		let lines = |text: &str, wrap: TextWrap| layout_of(text, TextStyle::default().set_wrap(wrap), width, None).lines;
+		let by_word = lines("mmm mmm", TextWrap::Word);
+		assert_eq!(by_word.len(), 2);
+		assert_eq!(by_word[1].range, 4..7);
+		let by_letter = lines("mmmmmm", TextWrap::Letter);
+		assert_eq!(by_letter.len(), 2);
+		assert!(by_letter.iter().all(|t| t.width <= word * 1.5));
+		assert_eq!(lines("mmm mmm", TextWrap::None).len(), 1);
+		assert_eq!(lines("m\nm", TextWrap::None).len(), 2);
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn horizontal_and_vertical_align() {
+		let layout = |align: TextAlign, vertical_align: TextVerticalAlign| layout_of("mm mm mm", TextStyle::default().set_align(align).set_vertical_align(vertical_align), Some(100.0), Some(200.0));
+		let center = layout(TextAlign::Center, TextVerticalAlign::Top);
+		assert!((center.lines[0].left * 2.0 + center.lines[0].width - 100.0).abs() < 1.0, "{:?}", center.lines[0]);
+		let right = layout(TextAlign::Right, TextVerticalAlign::Top);
+		assert!((right.lines[0].left + right.lines[0].width - 100.0).abs() < 1.0, "{:?}", right.lines[0]);
+		let bottom = layout(TextAlign::Left, TextVerticalAlign::Bottom);
+		let last = bottom.lines.last().unwrap();
+		assert!((last.top + last.height - 200.0).abs() < 1e-3, "{last:?}");
+		let middle = layout(TextAlign::Left, TextVerticalAlign::Middle);
+		assert!((middle.lines[0].top * 2.0 - (200.0 - middle.lines.len() as f32 * middle.line_height)).abs() < 1e-3);
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn justify_fills_every_line_but_the_last() {
+		let word = layout_of("mm", TextStyle::default(), None, None).lines[0].width;
+		let width = word * 3.5;
+		let layout = layout_of("mm m mm mm", TextStyle::default().set_align(TextAlign::Justify), Some(width), None);
+		assert_eq!(layout.lines.len(), 2);
+		assert!(layout.lines[0].justify_spacing > 0.0);
+		let first = &layout.lines[0];
+		let end = layout.glyphs[first.glyphs.clone()].iter().rev().find(|t| !t.character.is_whitespace()).map(|t| t.origin.x + t.advance).unwrap();
+		assert!((end - width).abs() < 1.0, "{end} {width}");
+		assert_eq!(layout.lines[1].justify_spacing, 0.0);
+	}
+
+	fn two_spans() -> RichText {
+		RichText {
+			spans: vec!(TextSpan::new("ab", TextStyle::default().set_font_size(10.0)), TextSpan::new("cd", TextStyle::default().set_font_size(30.0)).color([255, 0, 0, 255])),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn rich_text_spans_join() {
+		let text = two_spans();
+		assert_eq!(text.text(), "abcd");
+		assert_eq!(text.span_ranges(), vec!(0..2, 2..4));
+		// spans are only known after layout
+		#[cfg(feature = "default-font")]
+		{
+			let svg = text.into_svg(&Style::default());
+			assert!(svg.contains("font-size=\"30\"") && svg.contains("fill=\"rgb(255,0,0)\""), "{svg}");
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn rich_text_spans_share_lines() {
+		let text = two_spans();
+		let layout = text.layout().unwrap();
+		assert_eq!(layout.lines.len(), 1);
+		assert_eq!(layout.glyphs.iter().map(|t| (t.span, t.font_size)).collect::<Vec<_>>(), vec!((0, 10.0), (0, 10.0), (1, 30.0), (1, 30.0)));
+		// the larger span decides height of the line
+		assert!(layout.lines[0].height >= 30.0);
+		#[cfg(feature = "vertexs")]
+		{
+			let (vertexs, _, _) = text.into_vertexs(&Style::default(), Vec2::same(400.0));
+			assert_eq!(vertexs.first().unwrap().color, Color::WHITE.normalized());
+			assert_eq!(vertexs.last().unwrap().color, [1.0, 0.0, 0.0, 1.0]);
+		}
+	}
+
+	/// "ab cd" wrapped after the space, every glyph is 10 pixels wide and every line is 20 pixels high.
+	fn wrapped_layout() -> TextLayout {
+		let glyph = |character: char, byte_offset: usize, x: f32, line: usize| LayoutGlyph {
+			character,
+			byte_offset,
+			origin: Vec2::new(x, line as f32 * 20.0 + 15.0),
+			advance: 10.0,
+			line,
+			..Default::default()
+		};
+		let line = |range: std::ops::Range<usize>, glyphs: std::ops::Range<usize>, index: usize| LayoutLine { range, glyphs, top: index as f32 * 20.0, height: 20.0, ..Default::default() };
+		TextLayout {
+			text: String::from("ab cd"),
+			glyphs: vec!(glyph('a', 0, 0.0, 0), glyph('b', 1, 10.0, 0), glyph(' ', 2, 20.0, 0), glyph('c', 3, 0.0, 1), glyph('d', 4, 10.0, 1)),
+			lines: vec!(line(0..3, 0..3, 0), line(3..5, 3..5, 1)),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn caret_hit_and_selection() {
+		let layout = wrapped_layout();
+		assert_eq!(layout.caret(1), Area::new(Vec2::new(10.0, 0.0), Vec2::new(10.0, 20.0)));
+		// offsets at wrapped line breaks belong to the later line
+		assert_eq!(layout.caret(3), Area::new(Vec2::new(0.0, 20.0), Vec2::new(0.0, 40.0)));
+		assert_eq!(layout.caret(5), Area::new(Vec2::new(20.0, 20.0), Vec2::new(20.0, 40.0)));
+		assert_eq!(layout.hit(Vec2::new(4.0, 5.0)), 0);
+		assert_eq!(layout.hit(Vec2::new(14.0, 25.0)), 4);
+		// stays before the trailing space of a wrapped line
+		assert_eq!(layout.hit(Vec2::new(100.0, 5.0)), 2);
+		assert_eq!(layout.hit(Vec2::new(100.0, 100.0)), 5);
+		assert_eq!(layout.selection(1..4), vec!(Area::new(Vec2::new(10.0, 0.0), Vec2::new(30.0, 20.0)), Area::new(Vec2::new(0.0, 20.0), Vec2::new(10.0, 40.0))));
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn caret_functions_use_character_indices() {
+		let text = Text { text: String::from("éa\nb"), ..Default::default() };
+		let style = Style { position: Vec2::same(100.0), ..Default::default() };
+		let caret = text.caret_area(&style, 1).unwrap();
+		assert_eq!(text.index_at(&style, caret.area[0] + Vec2::new(0.5, 1.0)).unwrap(), 1);
+		assert_eq!(text.line_of(3).unwrap(), 1);
+		assert_eq!(text.line_range(0).unwrap(), 0..2);
+		let selection = text.selection_areas(&style, 0..4).unwrap();
+		assert_eq!(selection.len(), 2);
+		assert_eq!(selection[0].area[0].x, 100.0);
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn overflow_modes() {
+		let width = layout_of("mmm", TextStyle::default(), None, None).lines[0].width;
+		let layout = |overflow: TextOverflow| layout_of("mmmmmmmmmm", TextStyle::default().set_wrap(TextWrap::None).set_overflow(overflow), Some(width), Some(100.0));
+		let visible = layout(TextOverflow::Visible);
+		assert_eq!((visible.text.as_str(), visible.clip), ("mmmmmmmmmm", None));
+		assert_eq!(layout(TextOverflow::Clip).clip, Some(Area::new(Vec2::ZERO, Vec2::new(width, 100.0))));
+		let end = layout(TextOverflow::Ellipsis);
+		assert!(end.text.starts_with('m') && end.text.ends_with('…') && end.lines[0].width <= width, "{:?}", end.text);
+		let middle = layout(TextOverflow::EllipsisMiddle);
+		assert!(middle.text.starts_with('m') && middle.text.ends_with('m') && middle.text.contains('…') && middle.lines[0].width <= width, "{:?}", middle.text);
+		let shrinked = layout(TextOverflow::ShrinkToFit);
+		assert!(shrinked.font_size < EM * CORRECTION && shrinked.lines[0].width <= width);
+		assert_eq!(shrinked.text, "mmmmmmmmmm");
+		// nothing changes when text fits
+		let fits = layout_of("m", TextStyle::default().set_overflow(TextOverflow::Ellipsis), Some(width), None);
+		assert_eq!(fits.text, "m");
+	}
+
+	#[test]
+	fn text_svg_uses_fill_paint() {
+		let style = Style {
+			fill_paint: Some(red_to_blue()),
+			..Default::default()
+		};
+		let svg = Text { text: String::from("ab"), ..Default::default() }.into_svg(&style);
+		assert!(svg.contains("<linearGradient") && svg.contains("fill=\"url(#"), "{svg}");
+	}
+
+	#[test]
+	fn image_fit_modes() {
+		let image = |fit: ImageFit| Image { size: Vec2::same(100.0), intrinsic_size: Some(Vec2::new(200.0, 100.0)), fit, ..Default::default() };
+		assert_eq!(image(ImageFit::Fill).layout(), (Area::new(Vec2::ZERO, Vec2::same(100.0)), Area::new(Vec2::ZERO, Vec2::same(1.0))));
+		assert_eq!(image(ImageFit::Contain).layout(), (Area::new(Vec2::new(0.0, 25.0), Vec2::new(100.0, 75.0)), Area::new(Vec2::ZERO, Vec2::same(1.0))));
+		assert_eq!(image(ImageFit::Cover).layout(), (Area::new(Vec2::ZERO, Vec2::same(100.0)), Area::new(Vec2::new(0.25, 0.0), Vec2::new(0.75, 1.0))));
+		assert_eq!(image(ImageFit::None).layout(), image(ImageFit::Cover).layout());
+		let source = Image { source: Some(Area::new(Vec2::ZERO, Vec2::same(100.0))), ..image(ImageFit::Fill) };
+		assert_eq!(source.layout().1, Area::new(Vec2::ZERO, Vec2::new(0.5, 1.0)));
+	}
+
+	#[test]
+	fn image_tint() {
+		let image = Image { id: String::from("a"), size: Vec2::same(10.0), tint: [255, 0, 0, 128].into(), ..Default::default() };
+		let svg = image.into_svg(&Style::default());
+		assert!(svg.contains("<feColorMatrix type=\"matrix\" values=\"1 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0 0.5019608 0\"/>") && svg.contains(" filter=\"url(#nablo_tint_"), "{svg}");
+		assert!(!Image { tint: Color::WHITE, ..image.clone() }.into_svg(&Style::default()).contains("filter"));
+		#[cfg(feature = "vertexs")]
+		{
+			let (vertexs, _, _) = image.into_vertexs(&Style::default(), Vec2::same(100.0));
+			assert!(!vertexs.is_empty() && vertexs.iter().all(|t| t.color == image.tint.normalized()));
+		}
+	}
+
+	#[test]
+	fn nine_slice_patches() {
+		let image = Image {
+			size: Vec2::new(100.0, 50.0),
+			intrinsic_size: Some(Vec2::same(30.0)),
+			nine_slice: Some(NineSlice { left: 10.0, top: 10.0, right: 10.0, bottom: 10.0 }),
+			..Default::default()
+		};
+		let patches = image.patches();
+		assert_eq!(patches.len(), 9);
+		assert_eq!(patches[0], (Area::new(Vec2::ZERO, Vec2::same(10.0)), Area::new(Vec2::ZERO, Vec2::same(1.0 / 3.0))));
+		assert_eq!(patches[4], (Area::new(Vec2::same(10.0), Vec2::new(90.0, 40.0)), Area::new(Vec2::same(1.0 / 3.0), Vec2::same(2.0 / 3.0))));
+		assert_eq!(patches[8].0, Area::new(Vec2::new(90.0, 40.0), Vec2::new(100.0, 50.0)));
+		// corners shrink together and the empty middle is skipped
+		let small = Image { size: Vec2::same(15.0), ..image };
+		let patches = small.patches();
+		assert_eq!(patches.len(), 4);
+		assert_eq!(patches[0].0, Area::new(Vec2::ZERO, Vec2::same(7.5)));
+	}
+
+	#[test]
+	fn sprite_frames_wrap_inside_source() {
+		let sprite = SpriteSheet { frame: 7, frame_count: 6, ..SpriteSheet::new(4, 2) };
+		let sheet = Area::new(Vec2::same(10.0), Vec2::new(90.0, 50.0));
+		assert_eq!(sprite.frame_area(sheet), Area::new(Vec2::new(30.0, 10.0), Vec2::new(50.0, 30.0)));
+		assert_eq!(SpriteSheet { frame: 5, ..sprite }.frame_area(sheet), Area::new(Vec2::new(30.0, 30.0), Vec2::new(50.0, 50.0)));
+		let image = Image { size: Vec2::same(20.0), source: Some(sheet), intrinsic_size: Some(Vec2::same(100.0)), sprite: Some(sprite), ..Default::default() };
+		assert_eq!(image.source_area(), sprite.frame_area(sheet));
+		assert_eq!(image.layout().1, Area::new(Vec2::new(0.3, 0.1), Vec2::new(0.5, 0.3)));
+	}
+
+	#[test]
+	fn image_change_applies_delta() {
+		let before = Image { size: Vec2::same(10.0), source: Some(Area::new(Vec2::ZERO, Vec2::same(4.0))), ..Default::default() };
+		let after = Image { size: Vec2::same(15.0), source: Some(Area::new(Vec2::same(1.0), Vec2::same(5.0))), tint: Color::BLACK, fit: ImageFit::Cover, nine_slice: Some(NineSlice::default()), ..before.clone() };
+		let delta = after.delta(&before);
+		let mut other = Image { id: String::from("other"), size: Vec2::same(20.0), source: Some(Area::new(Vec2::ZERO, Vec2::same(8.0))), ..Default::default() };
+		other.change(&delta);
+		assert_eq!(other.id, "other");
+		assert_eq!(other.size, Vec2::same(25.0));
+		assert_eq!(other.source, Some(Area::new(Vec2::same(1.0), Vec2::same(9.0))));
+		assert_eq!((other.tint, other.fit, other.nine_slice), (after.tint, after.fit, after.nine_slice));
+	}
+
+	#[test]
+	fn text_path_circle_and_angles() {
+		let text_path = TextPath {
+			text: String::from("a"),
+			path: TextPathGuide::Circle(Circle { radius: 50.0 }),
+			rotate: PI,
+			..Default::default()
+		};
+		// placed like a circle shape, starts at right and goes clockwise
+		let (point, angle) = text_path.point_at(0.0).unwrap();
+		assert!((point - Vec2::new(100.0, 50.0)).len() < 1e-3, "{point:?}");
+		assert!((angle - PI / 2.0).abs() < 0.1, "{angle}");
+		let svg = text_path.into_svg(&Style::default());
+		assert!(svg.contains("d=\"M 100, 50 ") && svg.contains("rotate=\"180\""), "{svg}");
+	}
+
+	#[test]
+	#[cfg(all(feature = "vertexs", feature = "default-font"))]
+	fn glyphs_use_fill_paint() {
+		let style = Style {
+			fill_paint: Some(red_to_blue()),
+			..Default::default()
+		};
+		let text = Text {
+			text: String::from("mmmm"),
+			..Default::default()
+		};
+		let (vertexs, _, _) = text.into_vertexs(&style, Vec2::same(400.0));
+		assert!(!vertexs.is_empty());
+		let (first, last) = (vertexs[0].color, vertexs[vertexs.len() - 1].color);
+		assert!(first[0] > last[0] && first[2] < last[2], "{first:?} {last:?}");
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn outlines_follow_layout() {
+		let text = Text { text: String::from("H i"), ..Default::default() };
+		let layout = text.layout().unwrap();
+		let outlines = text.to_outlines().unwrap();
+		// spaces have no outline
+		assert_eq!(outlines.iter().map(|t| t.character).collect::<String>(), "Hi");
+		for (outline, glyph) in outlines.iter().zip(layout.glyphs.iter().filter(|t| t.character != ' ')) {
+			assert!(matches!(outline.commands.first(), Some(PathCommand::MoveTo(_))));
+			assert_eq!(outline.commands.last(), Some(&PathCommand::Close));
+			for command in &outline.commands {
+				if let PathCommand::MoveTo(t) | PathCommand::LineTo(t) = command {
+					// bitmaps are rounded to pixels
+					assert!(t.x >= glyph.area.area[0].x - 1.0 && t.x <= glyph.area.area[1].x + 1.0, "{t:?} {:?}", glyph.area);
+					assert!(t.y >= glyph.area.area[0].y - 1.0 && t.y <= glyph.area.area[1].y + 1.0, "{t:?} {:?}", glyph.area);
+				}
+			}
+		}
+		let mut moved = outlines[0].clone();
+		moved.move_delta_to(Vec2::same(10.0));
+		assert_ne!(moved, outlines[0]);
+		assert!(!text.to_polygons(0.5).unwrap().is_empty());
+		assert!(ShapeMask::Text(text).to_outlines().is_some_and(|t| t.len() == 2));
+		assert!(ShapeMask::Circle(Circle::default()).to_outlines().is_none());
+	}
+
+	#[test]
+	fn decoration_svg() {
+		assert_eq!(TextStyle::default().svg_decoration(), "none");
+		let all = TextDecoration { underline: true, overline: true, strikethrough: true };
+		assert_eq!(all.svg(), "underline overline line-through");
+		let text_style = TextStyle::default().set_decoration(TextDecoration { underline: true, ..Default::default() }).set_decoration_thickness(2.0);
+		assert_eq!(text_style.svg_decoration(), "underline 2px");
+		let svg = Text { text: String::from("ab"), text_style, ..Default::default() }.into_svg(&Style::default());
+		assert!(svg.contains("text-decoration=\"underline 2px\""), "{svg}");
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn decorations_are_placed_around_baseline() {
+		let text_style = TextStyle::default()
+			.set_decoration(TextDecoration { underline: true, overline: true, strikethrough: true })
+			.set_decoration_thickness(2.0)
+			.set_decoration_color([255, 0, 0, 255]);
+		let layout = layout_of("ab cd ", text_style, None, None);
+		let line = &layout.lines[0];
+		let kinds: Vec<DecorationKind> = layout.decorations.iter().map(|t| t.kind).collect();
+		assert_eq!(kinds, vec!(DecorationKind::Underline, DecorationKind::Overline, DecorationKind::Strikethrough));
+		let [underline, overline, strikethrough] = [0, 1, 2].map(|t| layout.decorations[t].area);
+		assert!(underline.area[0].y >= line.baseline);
+		assert!(overline.area[1].y < strikethrough.area[0].y && strikethrough.area[1].y < line.baseline);
+		for decoration in &layout.decorations {
+			assert_eq!(decoration.area.height(), 2.0);
+			assert_eq!(decoration.color, Some([255, 0, 0, 255].into()));
+			// the trailing space is left out
+			assert_eq!(decoration.area.area[1].x, line.left + line.width);
+		}
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn decorations_follow_spans() {
+		let mut rich_text = two_spans();
+		rich_text.spans[1] = rich_text.spans[1].clone().decoration(TextDecoration { strikethrough: true, ..Default::default() });
+		let decorations = rich_text.decorations().unwrap();
+		assert_eq!(decorations.len(), 1);
+		assert_eq!((decorations[0].kind, decorations[0].span, decorations[0].color), (DecorationKind::Strikethrough, 1, None));
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn font_metrics_are_read_from_font() {
+		let text = Text { text: String::from("x"), text_style: TextStyle::default().set_font_size(20.0), ..Default::default() };
+		let metrics = text.metrics().unwrap();
+		assert_eq!(metrics.font_size, 20.0);
+		assert!(metrics.ascent > 0.0 && metrics.descent < 0.0, "{metrics:?}");
+		assert!(metrics.cap_height > metrics.x_height && metrics.x_height > 0.0, "{metrics:?}");
+		assert!(metrics.underline_position < 0.0 && metrics.strikethrough_position > 0.0, "{metrics:?}");
+		assert!(metrics.underline_thickness > 0.0);
+		// one for every span
+		assert_eq!(two_spans().layout().unwrap().metrics.len(), 2);
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn glyphs_know_where_they_are() {
+		let layout = layout_of("aé\nb", TextStyle::default(), None, None);
+		let glyph = |character: char| layout.glyphs.iter().find(|t| t.character == character).unwrap();
+		assert_eq!((glyph('a').byte_offset, glyph('a').line), (0, 0));
+		assert_eq!((glyph('é').byte_range(), glyph('é').line), (1..3, 0));
+		assert_eq!((glyph('b').byte_offset, glyph('b').line), (4, 1));
+		assert!(glyph('a').origin.x + glyph('a').advance <= glyph('é').origin.x + 0.01);
+		assert_eq!(glyph('b').origin.y, layout.lines[1].baseline);
+		assert_eq!(layout.glyphs.iter().map(|t| (t.span, t.font_index)).max(), Some((0, 0)));
+	}
+
+	#[test]
+	#[cfg(feature = "default-font")]
+	fn rotated_text_is_transformed() {
+		let text = Text { text: String::from("mmmm"), ..Default::default() };
+		let style = Style {
+			position: Vec2::same(100.0),
+			rotate: PI / 2.0,
+			size: Vec2::new(2.0, 1.0),
+			..Default::default()
+		};
+		let area = text.layout().unwrap().area;
+		let rotated = text.get_area(&style);
+		assert_eq!(rotated, area.transform(&style));
+		assert!((rotated.width() - area.height() * 2.0).abs() < 1e-3 && (rotated.height() - area.width()).abs() < 1e-3, "{rotated:?} {area:?}");
+		// carets lie down with the text
+		let caret = text.caret_area(&style, 2).unwrap();
+		assert!(caret.height() < 1e-3 && caret.width() > 0.0, "{caret:?}");
+		assert_eq!(text.index_at(&style, caret.center()).unwrap(), 2);
+		assert_eq!(text.selection_areas(&style, 0..4).unwrap()[0].height(), text.layout().unwrap().lines[0].width);
+		let svg = text.into_svg(&style);
+		assert!(svg.contains(&style.svg_transform()) || svg.contains(&style.svg_placed_transform()), "{svg}");
+	}
+}